@@ -0,0 +1,484 @@
+use std::{
+    collections::VecDeque,
+    io::{IsTerminal, Read},
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::runner::PART_TIMEOUT_VARIABLE;
+
+/// A day crate from the workspace, it is run from its own folder so it finds its input.txt
+#[derive(Clone)]
+pub struct Day {
+    pub number: u32,
+    pub package: String,
+    pub folder: PathBuf,
+}
+
+pub struct Options {
+    /// Number of days that are run at the same time
+    pub jobs: usize,
+    /// Days taking longer than this are highlighted in the summary
+    pub slow_threshold: Duration,
+    /// Print the full output of each day when it finishes
+    pub verbose: bool,
+    /// Only run these days, runs all days when empty
+    pub days: Vec<u32>,
+    /// Time budget per part, days that run their parts with a timeout through the runner report
+    /// TIMEOUT for parts exceeding it, other days are stopped once both parts could have used
+    /// up their budget
+    pub part_timeout: Option<Duration>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            jobs: thread::available_parallelism().map_or(1, |n| n.get()),
+            slow_threshold: Duration::from_secs(1),
+            verbose: false,
+            days: vec![],
+            part_timeout: None,
+        }
+    }
+}
+
+pub struct DayResult {
+    pub day: u32,
+    pub elapsed: Duration,
+    pub outcome: Outcome,
+}
+
+pub enum Outcome {
+    Finished {
+        output: String,
+        answers: Vec<Answer>,
+    },
+    TimedOut {
+        output: String,
+        answers: Vec<Answer>,
+    },
+    Failed(String),
+}
+
+/// An answer as printed by a day, either through the runner or with a plain `part 1: {}`
+#[derive(Debug, PartialEq)]
+pub struct Answer {
+    pub part: u32,
+    pub value: String,
+    pub duration: Option<String>,
+}
+
+/// Builds all days in release mode, runs them in parallel and prints a summary sorted by day
+pub fn run(workspace_root: &Path, options: &Options) {
+    let days: Vec<Day> = find_days(workspace_root)
+        .into_iter()
+        .filter(|d| options.days.is_empty() || options.days.contains(&d.number))
+        .collect();
+
+    build(workspace_root);
+    let binary_folder = binary_folder(workspace_root);
+
+    let queue = Arc::new(Mutex::new(days.into_iter().collect::<VecDeque<Day>>()));
+    let (sender, receiver) = mpsc::channel();
+
+    let workers: Vec<_> = (0..options.jobs.max(1))
+        .map(|_| {
+            let queue = Arc::clone(&queue);
+            let sender = sender.clone();
+            let binary_folder = binary_folder.clone();
+            let part_timeout = options.part_timeout;
+
+            thread::spawn(move || loop {
+                let Some(day) = queue.lock().unwrap().pop_front() else {
+                    break;
+                };
+                let result = run_day(&day, &binary_folder, part_timeout);
+                if sender.send(result).is_err() {
+                    break;
+                }
+            })
+        })
+        .collect();
+
+    drop(sender);
+
+    let mut results = vec![];
+
+    for result in receiver {
+        print_finished(&result, options);
+        results.push(result);
+    }
+
+    for worker in workers {
+        worker.join().expect("Worker thread panicked");
+    }
+
+    results.sort_by_key(|r| r.day);
+    print_summary(&results, options.slow_threshold);
+}
+
+/// Reads the uncommented day crates from the workspace Cargo.toml
+pub fn find_days(workspace_root: &Path) -> Vec<Day> {
+    let manifest = std::fs::read_to_string(workspace_root.join("Cargo.toml"))
+        .expect("Workspace Cargo.toml could not be read");
+
+    let mut days: Vec<Day> = manifest
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.starts_with('#'))
+        .filter_map(|l| l.trim_end_matches(',').strip_prefix('"')?.strip_suffix('"'))
+        .filter_map(|folder_name| {
+            let number = folder_name.strip_prefix("day_")?.parse().ok()?;
+            let folder = workspace_root.join(folder_name);
+            let package = read_package_name(&folder);
+            Some(Day {
+                number,
+                package,
+                folder,
+            })
+        })
+        .collect();
+
+    days.sort_by_key(|d| d.number);
+    days
+}
+
+fn read_package_name(folder: &Path) -> String {
+    let manifest = std::fs::read_to_string(folder.join("Cargo.toml"))
+        .expect("Day Cargo.toml could not be read");
+
+    manifest
+        .lines()
+        .find_map(|l| l.trim().strip_prefix("name")?.trim().strip_prefix('='))
+        .expect("Day Cargo.toml has no name")
+        .trim()
+        .trim_matches('"')
+        .to_owned()
+}
+
+fn build(workspace_root: &Path) {
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_owned());
+    let status = Command::new(cargo)
+        .args(["build", "--release", "--workspace", "--bins"])
+        .current_dir(workspace_root)
+        .status()
+        .expect("Cargo could not be started");
+
+    assert!(status.success(), "Building the days failed");
+}
+
+fn binary_folder(workspace_root: &Path) -> PathBuf {
+    std::env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| workspace_root.join("target"))
+        .join("release")
+}
+
+fn run_day(day: &Day, binary_folder: &Path, part_timeout: Option<Duration>) -> DayResult {
+    let binary = binary_folder.join(format!("{}{}", day.package, std::env::consts::EXE_SUFFIX));
+
+    let mut command = Command::new(&binary);
+    command
+        .current_dir(&day.folder)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    if let Some(timeout) = part_timeout {
+        command.env(PART_TIMEOUT_VARIABLE, timeout.as_secs_f64().to_string());
+    }
+
+    let now = Instant::now();
+
+    let child = match command.spawn() {
+        Ok(child) => child,
+        Err(error) => {
+            return DayResult {
+                day: day.number,
+                elapsed: now.elapsed(),
+                outcome: Outcome::Failed(format!("could not start {}: {}", binary.display(), error)),
+            }
+        }
+    };
+
+    // two parts, plus some slack for parsing and the runner writing its own TIMEOUT
+    let deadline = part_timeout.map(|timeout| now + timeout * 2 + Duration::from_secs(1));
+    let (status, stdout, stderr) = wait_with_deadline(child, deadline);
+    let elapsed = now.elapsed();
+
+    let outcome = match status {
+        None => {
+            let answers = parse_answers(&stdout);
+            Outcome::TimedOut {
+                output: stdout,
+                answers,
+            }
+        }
+        Some(status) if !status.success() => {
+            let reason = stderr.lines().find(|l| l.contains("panicked")).unwrap_or("");
+            Outcome::Failed(format!("{} {}", status, reason).trim().to_owned())
+        }
+        Some(_) => {
+            let answers = parse_answers(&stdout);
+            Outcome::Finished {
+                output: stdout,
+                answers,
+            }
+        }
+    };
+
+    DayResult {
+        day: day.number,
+        elapsed,
+        outcome,
+    }
+}
+
+/// Waits for the child to exit, or kills it once the deadline has passed (status is then None)
+fn wait_with_deadline(
+    mut child: Child,
+    deadline: Option<Instant>,
+) -> (Option<std::process::ExitStatus>, String, String) {
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+
+    let status = loop {
+        if let Some(status) = child.try_wait().expect("Could not wait for day") {
+            break Some(status);
+        }
+        if deadline.is_some_and(|deadline| Instant::now() > deadline) {
+            _ = child.kill();
+            _ = child.wait();
+            break None;
+        }
+        thread::sleep(Duration::from_millis(5));
+    };
+
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+    (status, stdout, stderr)
+}
+
+fn read_in_background(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut bytes = vec![];
+        if let Some(mut pipe) = pipe {
+            _ = pipe.read_to_end(&mut bytes);
+        }
+        String::from_utf8_lossy(&bytes).into_owned()
+    })
+}
+
+/// Finds the answers in the output of a day, supporting both the runner format:
+/// ```text
+/// Part 1
+/// Duration: 1.2ms
+/// ----------------
+/// 42
+/// ```
+/// and the `part 1: 42` format, where the answer may also start on the next line
+pub fn parse_answers(output: &str) -> Vec<Answer> {
+    let lines: Vec<&str> = output.lines().collect();
+    let mut answers = vec![];
+    let mut index = 0;
+
+    while index < lines.len() {
+        let line = lines[index].trim();
+        index += 1;
+
+        let Some(rest) = line
+            .strip_prefix("Part ")
+            .or_else(|| line.strip_prefix("part "))
+        else {
+            continue;
+        };
+
+        let (number, inline_value) = match rest.split_once(':') {
+            Some((number, value)) => (number, Some(value.trim())),
+            None => (rest, None),
+        };
+
+        let Ok(part) = number.trim().parse() else {
+            continue;
+        };
+
+        let mut duration = None;
+
+        if inline_value.is_none() {
+            if let Some(value) = lines.get(index).and_then(|l| l.strip_prefix("Duration: ")) {
+                duration = Some(value.trim().to_owned());
+                index += 1;
+            }
+            if lines.get(index).is_some_and(|l| l.starts_with("---")) {
+                index += 1;
+            }
+        }
+
+        let mut value_lines = vec![];
+
+        match inline_value {
+            Some(value) if !value.is_empty() => value_lines.push(value),
+            _ => {
+                while let Some(line) = lines.get(index) {
+                    if line.trim().is_empty() || line.to_lowercase().starts_with("part ") {
+                        break;
+                    }
+                    value_lines.push(line.trim_end());
+                    index += 1;
+                }
+            }
+        }
+
+        answers.push(Answer {
+            part,
+            value: value_lines.join("\n"),
+            duration,
+        });
+    }
+
+    answers
+}
+
+fn print_finished(result: &DayResult, options: &Options) {
+    match &result.outcome {
+        Outcome::Finished { output, .. } => {
+            println!("Day {} finished in {:.2?}", result.day, result.elapsed);
+            if options.verbose {
+                println!("{}", output);
+            }
+        }
+        Outcome::TimedOut { output, .. } => {
+            println!("Day {} stopped after {:.2?}: TIMEOUT", result.day, result.elapsed);
+            if options.verbose {
+                println!("{}", output);
+            }
+        }
+        Outcome::Failed(reason) => {
+            println!("Day {} failed after {:.2?}: {}", result.day, result.elapsed, reason)
+        }
+    }
+}
+
+pub fn print_summary(results: &[DayResult], slow_threshold: Duration) {
+    let rows: Vec<[String; 4]> = results
+        .iter()
+        .map(|r| {
+            let (part_1, part_2) = match &r.outcome {
+                Outcome::Finished { answers, .. } => {
+                    (summarize(answers, 1, "-"), summarize(answers, 2, "-"))
+                }
+                Outcome::TimedOut { answers, .. } => (
+                    summarize(answers, 1, "TIMEOUT"),
+                    summarize(answers, 2, "TIMEOUT"),
+                ),
+                Outcome::Failed(reason) => ("FAILED".to_owned(), reason.clone()),
+            };
+            [
+                r.day.to_string(),
+                part_1,
+                part_2,
+                format!("{:.2?}", r.elapsed),
+            ]
+        })
+        .collect();
+
+    let headers = ["Day", "Part 1", "Part 2", "Time"];
+    let widths: Vec<usize> = (0..headers.len())
+        .map(|i| {
+            rows.iter()
+                .map(|r| r[i].chars().count())
+                .chain([headers[i].len()])
+                .max()
+                .unwrap()
+        })
+        .collect();
+
+    let colored = std::io::stdout().is_terminal();
+
+    println!();
+    println!("{}", format_row(&headers.map(|h| h.to_owned()), &widths));
+    println!(
+        "{}",
+        widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<String>>().join("-+-")
+    );
+
+    for (row, result) in rows.iter().zip(results) {
+        let line = format_row(row, &widths);
+        let slow = result.elapsed > slow_threshold;
+
+        match (slow, colored) {
+            (true, true) => println!("\x1B[33m{}\x1B[0m", line),
+            (true, false) => println!("{} (slow)", line),
+            (false, _) => println!("{}", line),
+        }
+    }
+
+    let total: Duration = results.iter().map(|r| r.elapsed).sum();
+    println!();
+    println!("Total time (all days added up): {:.2?}", total);
+}
+
+fn summarize(answers: &[Answer], part: u32, missing: &str) -> String {
+    match answers.iter().find(|a| a.part == part) {
+        None => missing.to_owned(),
+        Some(answer) if answer.value.contains('\n') => {
+            format!("({} lines)", answer.value.lines().count())
+        }
+        Some(answer) => answer.value.clone(),
+    }
+}
+
+fn format_row(row: &[String; 4], widths: &[usize]) -> String {
+    row.iter()
+        .zip(widths)
+        .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+        .collect::<Vec<String>>()
+        .join(" | ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_runner_output() {
+        let output = "Part 1\nDuration: 1.5ms\n----------------\n42\n\nPart 2\nDuration: 2s\n----------------\nABC\n\n";
+        assert_eq!(
+            parse_answers(output),
+            vec![
+                Answer {
+                    part: 1,
+                    value: "42".to_owned(),
+                    duration: Some("1.5ms".to_owned())
+                },
+                Answer {
+                    part: 2,
+                    value: "ABC".to_owned(),
+                    duration: Some("2s".to_owned())
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_plain_output() {
+        let output = "- / (dir)\npart 1: 13\npart 2: \n##  #\n #  #\n";
+        assert_eq!(
+            parse_answers(output),
+            vec![
+                Answer {
+                    part: 1,
+                    value: "13".to_owned(),
+                    duration: None
+                },
+                Answer {
+                    part: 2,
+                    value: "##  #\n #  #".to_owned(),
+                    duration: None
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+
+use crate::{collections::SparseGrid, vectors::Vec2D};
+
+/// Decides where the cells of an `Automaton` want to move, all cells decide at the same time
+pub trait Rule {
+    /// The cell this one wants to move to, None to stay where it is
+    /// Cells only move into cells that were empty at the start of the round
+    fn propose(&self, cell: Vec2D, grid: &SparseGrid, round: usize) -> Option<Vec2D>;
+
+    /// Picks which of the cells that want to move to the same target may go there
+    /// By default none of them move
+    fn resolve(&self, _target: Vec2D, _contenders: &[Vec2D]) -> Option<Vec2D> {
+        None
+    }
+}
+
+/// Moves the cells of a sparse grid round after round, following a `Rule`
+pub struct Automaton<R: Rule> {
+    grid: SparseGrid,
+    rule: R,
+    round: usize,
+}
+
+impl<R: Rule> Automaton<R> {
+    pub fn new(grid: SparseGrid, rule: R) -> Self {
+        Self {
+            grid,
+            rule,
+            round: 0,
+        }
+    }
+
+    pub fn grid(&self) -> &SparseGrid {
+        &self.grid
+    }
+
+    /// The number of rounds played so far
+    pub fn round(&self) -> usize {
+        self.round
+    }
+
+    /// Plays one round and returns how many cells moved
+    pub fn step(&mut self) -> usize {
+        let mut proposals: HashMap<Vec2D, Vec<Vec2D>> = HashMap::new();
+
+        for cell in self.grid.iter() {
+            let target = self.rule.propose(cell, &self.grid, self.round);
+            if let Some(target) = target.filter(|t| !self.grid.contains(*t)) {
+                proposals.entry(target).or_default().push(cell);
+            }
+        }
+
+        let moves: Vec<(Vec2D, Vec2D)> = proposals
+            .into_iter()
+            .filter_map(|(target, contenders)| match contenders[..] {
+                [cell] => Some((cell, target)),
+                _ => self
+                    .rule
+                    .resolve(target, &contenders)
+                    .map(|cell| (cell, target)),
+            })
+            .collect();
+
+        for (cell, target) in &moves {
+            self.grid.remove(*cell);
+            self.grid.insert(*target);
+        }
+
+        self.round += 1;
+        moves.len()
+    }
+
+    pub fn run(&mut self, rounds: usize) {
+        for _ in 0..rounds {
+            self.step();
+        }
+    }
+
+    /// Plays until a round in which nothing moves, and returns the number of that round
+    pub fn run_until_stable(&mut self) -> usize {
+        while self.step() > 0 {}
+        self.round
+    }
+}
+
+/// A list of options where a different one goes first every round,
+/// e.g. the directions the elves of 2022 day 23 look in
+pub struct RotatingPriorities<T> {
+    options: Vec<T>,
+}
+
+impl<T> RotatingPriorities<T> {
+    pub fn new(options: Vec<T>) -> Self {
+        Self { options }
+    }
+
+    /// All options in the order they are tried in the given round
+    pub fn in_round(&self, round: usize) -> impl Iterator<Item = &T> {
+        let start = round % self.options.len().max(1);
+        self.options[start..].iter().chain(&self.options[..start])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every cell moves right, unless another cell is in the way
+    struct Right;
+
+    impl Rule for Right {
+        fn propose(&self, cell: Vec2D, grid: &SparseGrid, _round: usize) -> Option<Vec2D> {
+            let target = cell + Vec2D::new(1, 0);
+            (target.x < 3 && !grid.contains(target)).then_some(target)
+        }
+    }
+
+    /// Every cell moves to the origin, the left most one wins
+    struct Gather;
+
+    impl Rule for Gather {
+        fn propose(&self, _cell: Vec2D, _grid: &SparseGrid, _round: usize) -> Option<Vec2D> {
+            Some(Vec2D::new(0, 0))
+        }
+
+        fn resolve(&self, _target: Vec2D, contenders: &[Vec2D]) -> Option<Vec2D> {
+            contenders.iter().min_by_key(|c| c.x).copied()
+        }
+    }
+
+    /// Grains of sand fall down, or slide down to the left or right, until they land on the floor
+    /// A grain falling straight down goes before one sliding into the same cell
+    struct Sand {
+        floor: i32,
+    }
+
+    impl Rule for Sand {
+        fn propose(&self, cell: Vec2D, grid: &SparseGrid, _round: usize) -> Option<Vec2D> {
+            [Vec2D::new(0, 1), Vec2D::new(-1, 1), Vec2D::new(1, 1)]
+                .into_iter()
+                .map(|step| cell + step)
+                .find(|target| target.y < self.floor && !grid.contains(*target))
+        }
+
+        fn resolve(&self, target: Vec2D, contenders: &[Vec2D]) -> Option<Vec2D> {
+            contenders
+                .iter()
+                .min_by_key(|c| (c.x != target.x, c.x))
+                .copied()
+        }
+    }
+
+    #[test]
+    fn moves_until_stable() {
+        let grid = SparseGrid::from_text("#.#.", '#');
+        let mut automaton = Automaton::new(grid, Right);
+
+        assert_eq!(automaton.step(), 1);
+        assert_eq!(automaton.grid().to_string(), "##\n");
+        assert_eq!(automaton.run_until_stable(), 2);
+        assert_eq!(automaton.grid().to_string(), "##\n");
+    }
+
+    #[test]
+    fn drops_sand() {
+        let grid = SparseGrid::from_text("##\n.#", '#');
+        let mut automaton = Automaton::new(grid, Sand { floor: 3 });
+
+        assert_eq!(automaton.step(), 2);
+        assert_eq!(automaton.grid().to_string(), ".#\n#.\n.#\n");
+        assert_eq!(automaton.run_until_stable(), 4);
+        assert_eq!(automaton.grid().to_string(), "###\n");
+        assert_eq!(automaton.grid().bounds().unwrap().0, Vec2D::new(0, 2));
+    }
+
+    #[test]
+    fn resolves_conflicts() {
+        let grid = SparseGrid::from_text(".#\n.#", '#');
+        let mut automaton = Automaton::new(grid, Gather);
+
+        assert_eq!(automaton.step(), 1);
+        assert!(automaton.grid().contains(Vec2D::new(0, 0)));
+        assert_eq!(automaton.grid().len(), 2);
+
+        let priorities = RotatingPriorities::new(vec!['a', 'b', 'c']);
+        assert_eq!(priorities.in_round(4).collect::<String>(), "bca");
+    }
+}
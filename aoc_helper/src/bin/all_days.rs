@@ -0,0 +1,55 @@
+use std::{path::Path, time::Duration};
+
+use aoc_helper::all_days::{self, Options};
+
+/// Runs all days of the workspace in parallel and prints a summary
+///
+/// Usage: cargo run --release --bin all_days -- [--jobs N] [--slow SECONDS] [--timeout SECONDS]
+///                                               [--verbose] [DAY...]
+fn main() {
+    let mut options = Options::default();
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--jobs" | "-j" => {
+                options.jobs = parse_next(&mut args, "--jobs expects a number");
+            }
+            "--slow" => {
+                options.slow_threshold = parse_seconds(&mut args, "--slow");
+            }
+            "--timeout" => {
+                options.part_timeout = Some(parse_seconds(&mut args, "--timeout"));
+            }
+            "--verbose" | "-v" => options.verbose = true,
+            day => options.days.push(
+                day.parse()
+                    .unwrap_or_else(|_| usage_error("Days should be given as numbers")),
+            ),
+        }
+    }
+
+    let workspace_root = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("aoc_helper should be inside the workspace");
+
+    all_days::run(workspace_root, &options);
+}
+
+fn parse_next<T: std::str::FromStr>(args: &mut impl Iterator<Item = String>, message: &str) -> T {
+    args.next()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| usage_error(message))
+}
+
+/// Seconds, fractions are allowed, negative or too large values are a usage error
+fn parse_seconds(args: &mut impl Iterator<Item = String>, flag: &str) -> Duration {
+    let message = format!("{} expects a positive number of seconds", flag);
+    let seconds: f64 = parse_next(args, &message);
+    Duration::try_from_secs_f64(seconds).unwrap_or_else(|_| usage_error(&message))
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(2)
+}
@@ -0,0 +1,246 @@
+/// Points to a value in an `IndexedRing`, it stays valid while the value moves around
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RingHandle(usize);
+
+/// A circular list where values can be moved by an offset and looked up by index in O(log n)
+/// Implemented as an implicit treap, the position of a node is the size of everything left of it
+pub struct IndexedRing<T> {
+    nodes: Vec<Node<T>>,
+    root: Option<usize>,
+    seed: u64,
+}
+
+struct Node<T> {
+    value: T,
+    priority: u64,
+    size: usize,
+    left: Option<usize>,
+    right: Option<usize>,
+    parent: Option<usize>,
+}
+
+impl<T> Default for IndexedRing<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> IndexedRing<T> {
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![],
+            root: None,
+            seed: 0x2545_f491_4f6c_dd1d,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Adds the value at the end, right before the first value
+    pub fn push(&mut self, value: T) -> RingHandle {
+        let index = self.nodes.len();
+        let priority = self.next_priority();
+
+        self.nodes.push(Node {
+            value,
+            priority,
+            size: 1,
+            left: None,
+            right: None,
+            parent: None,
+        });
+
+        self.root = self.merge(self.root, Some(index));
+        RingHandle(index)
+    }
+
+    pub fn get(&self, handle: RingHandle) -> &T {
+        &self.nodes[handle.0].value
+    }
+
+    pub fn get_mut(&mut self, handle: RingHandle) -> &mut T {
+        &mut self.nodes[handle.0].value
+    }
+
+    /// The current position of the value, counted from the start of the ring
+    pub fn index_of(&self, handle: RingHandle) -> usize {
+        let mut node = handle.0;
+        let mut index = self.size(self.nodes[node].left);
+
+        while let Some(parent) = self.nodes[node].parent {
+            if self.nodes[parent].right == Some(node) {
+                index += self.size(self.nodes[parent].left) + 1;
+            }
+            node = parent;
+        }
+
+        index
+    }
+
+    /// The value at the position, wrapping around the end of the ring
+    pub fn at(&self, index: usize) -> RingHandle {
+        assert!(!self.is_empty(), "Ring is empty");
+
+        let mut index = index % self.len();
+        let mut node = self.root.unwrap();
+
+        loop {
+            let left_size = self.size(self.nodes[node].left);
+
+            if index < left_size {
+                node = self.nodes[node].left.unwrap();
+            } else if index == left_size {
+                return RingHandle(node);
+            } else {
+                index -= left_size + 1;
+                node = self.nodes[node].right.unwrap();
+            }
+        }
+    }
+
+    /// Takes the value out of the ring and puts it back `offset` places further
+    /// While moving the value is not part of the ring, so moving len - 1 places ends where it started
+    pub fn move_by(&mut self, handle: RingHandle, offset: i64) {
+        if self.len() < 2 {
+            return;
+        }
+
+        let index = self.index_of(handle);
+        let (before, rest) = self.split(self.root, index);
+        let (moving, after) = self.split(rest, 1);
+        let remaining = self.merge(before, after);
+
+        let new_index = (index as i64 + offset).rem_euclid(self.len() as i64 - 1) as usize;
+        let (before, after) = self.split(remaining, new_index);
+        let before = self.merge(before, moving);
+        self.root = self.merge(before, after);
+    }
+
+    /// The values in ring order, starting at the start of the ring
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        (0..self.len()).map(|i| self.get(self.at(i)))
+    }
+
+    fn size(&self, node: Option<usize>) -> usize {
+        node.map_or(0, |n| self.nodes[n].size)
+    }
+
+    fn update(&mut self, node: usize) {
+        let (left, right) = (self.nodes[node].left, self.nodes[node].right);
+        self.nodes[node].size = 1 + self.size(left) + self.size(right);
+
+        for child in [left, right].into_iter().flatten() {
+            self.nodes[child].parent = Some(node);
+        }
+    }
+
+    /// Splits the tree into the first `count` values and the rest, both without a parent
+    fn split(&mut self, node: Option<usize>, count: usize) -> (Option<usize>, Option<usize>) {
+        let Some(node) = node else {
+            return (None, None);
+        };
+        self.nodes[node].parent = None;
+
+        let left_size = self.size(self.nodes[node].left);
+
+        if count <= left_size {
+            let (left, right) = self.split(self.nodes[node].left, count);
+            self.nodes[node].left = right;
+            self.update(node);
+            (left, Some(node))
+        } else {
+            let (left, right) = self.split(self.nodes[node].right, count - left_size - 1);
+            self.nodes[node].right = left;
+            self.update(node);
+            (Some(node), right)
+        }
+    }
+
+    /// Puts the values of `right` after the values of `left`
+    fn merge(&mut self, left: Option<usize>, right: Option<usize>) -> Option<usize> {
+        match (left, right) {
+            (None, tree) | (tree, None) => tree,
+            (Some(l), Some(r)) => {
+                if self.nodes[l].priority > self.nodes[r].priority {
+                    let merged = self.merge(self.nodes[l].right, right);
+                    self.nodes[l].right = merged;
+                    self.update(l);
+                    Some(l)
+                } else {
+                    let merged = self.merge(left, self.nodes[r].left);
+                    self.nodes[r].left = merged;
+                    self.update(r);
+                    Some(r)
+                }
+            }
+        }
+    }
+
+    /// xorshift, the priorities only need to look random to keep the tree balanced
+    fn next_priority(&mut self) -> u64 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        self.seed
+    }
+}
+
+impl<T> FromIterator<T> for IndexedRing<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut ring = IndexedRing::new();
+        for value in iter {
+            ring.push(value);
+        }
+        ring
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_and_position_agree() {
+        let ring: IndexedRing<usize> = (0..100).collect();
+
+        for i in 0..100 {
+            let handle = ring.at(i);
+            assert_eq!(*ring.get(handle), i);
+            assert_eq!(ring.index_of(handle), i);
+        }
+        assert_eq!(*ring.get(ring.at(250)), 50);
+    }
+
+    #[test]
+    fn moves_like_removing_and_inserting() {
+        let mut seed = 12345u64;
+        let mut random = move || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 33) as i64
+        };
+
+        let mut ring: IndexedRing<usize> = (0..50).collect();
+        let handles: Vec<RingHandle> = (0..50).map(|i| ring.at(i)).collect();
+        let mut expected: Vec<usize> = (0..50).collect();
+
+        for _ in 0..1000 {
+            let value = (random() % 50) as usize;
+            let offset = random() % 200 - 100;
+
+            ring.move_by(handles[value], offset);
+
+            let index = expected.iter().position(|v| *v == value).unwrap();
+            expected.remove(index);
+            let new_index = (index as i64 + offset).rem_euclid(49) as usize;
+            expected.insert(new_index, value);
+
+            assert_eq!(ring.iter().copied().collect::<Vec<_>>(), expected);
+        }
+    }
+}
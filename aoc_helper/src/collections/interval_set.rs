@@ -0,0 +1,215 @@
+use std::ops::RangeInclusive;
+
+/// A set of integers stored as sorted, disjoint and non-adjacent inclusive intervals
+/// Useful when ranges are large, e.g. the covered cells of one row of a huge grid
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+    intervals: Vec<(i64, i64)>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self { intervals: vec![] }
+    }
+
+    /// Adds all values of the range, merging it with overlapping or adjacent intervals
+    pub fn insert(&mut self, range: RangeInclusive<i64>) {
+        let (mut start, mut end) = range.into_inner();
+        if start > end {
+            return;
+        }
+
+        // first interval that could touch the new one, and the first one after it
+        let first = self.intervals.partition_point(|(_, e)| *e < start.saturating_sub(1));
+        let last = self.intervals.partition_point(|(s, _)| *s <= end.saturating_add(1));
+
+        if first < last {
+            start = start.min(self.intervals[first].0);
+            end = end.max(self.intervals[last - 1].1);
+        }
+
+        self.intervals.splice(first..last, [(start, end)]);
+    }
+
+    /// Removes all values of the range, splitting intervals where needed
+    pub fn remove(&mut self, range: RangeInclusive<i64>) {
+        let (start, end) = range.into_inner();
+        if start > end {
+            return;
+        }
+
+        let first = self.intervals.partition_point(|(_, e)| *e < start);
+        let last = self.intervals.partition_point(|(s, _)| *s <= end);
+
+        if first >= last {
+            return;
+        }
+
+        let mut remaining = vec![];
+
+        // there is nothing before i64::MIN or after i64::MAX to keep
+        let (first_start, _) = self.intervals[first];
+        if let Some(before) = start.checked_sub(1).filter(|b| first_start <= *b) {
+            remaining.push((first_start, before));
+        }
+
+        let (_, last_end) = self.intervals[last - 1];
+        if let Some(after) = end.checked_add(1).filter(|a| *a <= last_end) {
+            remaining.push((after, last_end));
+        }
+
+        self.intervals.splice(first..last, remaining);
+    }
+
+    /// Removes all values that are in `other`
+    pub fn subtract(&mut self, other: &IntervalSet) {
+        for range in other.iter() {
+            self.remove(range);
+        }
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        self.contains_range(value..=value)
+    }
+
+    /// True when every value of the range is in the set
+    pub fn contains_range(&self, range: RangeInclusive<i64>) -> bool {
+        let (start, end) = range.into_inner();
+        if start > end {
+            return true;
+        }
+
+        let index = self.intervals.partition_point(|(_, e)| *e < start);
+        self.intervals
+            .get(index)
+            .is_some_and(|(s, e)| *s <= start && end <= *e)
+    }
+
+    /// True when at least one value of the range is in the set
+    pub fn intersects(&self, range: RangeInclusive<i64>) -> bool {
+        let (start, end) = range.into_inner();
+        if start > end {
+            return false;
+        }
+
+        let index = self.intervals.partition_point(|(_, e)| *e < start);
+        self.intervals.get(index).is_some_and(|(s, _)| *s <= end)
+    }
+
+    /// The amount of values in the set
+    pub fn len(&self) -> u64 {
+        self.intervals
+            .iter()
+            .map(|(start, end)| start.abs_diff(*end) + 1)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The amount of values of the range that are in the set
+    pub fn coverage(&self, range: RangeInclusive<i64>) -> u64 {
+        if range.is_empty() {
+            return 0;
+        }
+        let (start, end) = range.into_inner();
+
+        self.intervals
+            .iter()
+            .filter(|(s, e)| *s <= end && *e >= start)
+            .map(|(s, e)| (*s).max(start).abs_diff((*e).min(end)) + 1)
+            .sum()
+    }
+
+    /// The ranges within `bounds` that are not in the set, in ascending order
+    pub fn gaps(&self, bounds: RangeInclusive<i64>) -> Vec<RangeInclusive<i64>> {
+        if bounds.is_empty() {
+            return vec![];
+        }
+        let (start, end) = bounds.into_inner();
+        let mut gaps = vec![];
+        let mut next = start;
+
+        for (s, e) in self.intervals.iter().filter(|(s, e)| *s <= end && *e >= start) {
+            if *s > next {
+                gaps.push(next..=*s - 1);
+            }
+            if *e >= end {
+                return gaps;
+            }
+            next = *e + 1;
+        }
+
+        if next <= end {
+            gaps.push(next..=end);
+        }
+
+        gaps
+    }
+
+    /// The intervals of the set in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<i64>> + '_ {
+        self.intervals.iter().map(|(start, end)| *start..=*end)
+    }
+}
+
+impl FromIterator<RangeInclusive<i64>> for IntervalSet {
+    fn from_iter<T: IntoIterator<Item = RangeInclusive<i64>>>(iter: T) -> Self {
+        let mut set = IntervalSet::new();
+        for range in iter {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_merges_overlapping_and_adjacent() {
+        let set = IntervalSet::from_iter([1..=3, 10..=12, 4..=5, 11..=20, 30..=30]);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![1..=5, 10..=20, 30..=30]);
+        assert_eq!(set.len(), 17);
+        assert!(set.contains_range(11..=19));
+        assert!(!set.contains_range(5..=10));
+        assert!(set.intersects(5..=10));
+        assert!(!set.intersects(21..=29));
+    }
+
+    #[test]
+    fn remove_splits_intervals() {
+        let mut set = IntervalSet::from_iter([0..=10, 20..=30]);
+        set.remove(5..=22);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![0..=4, 23..=30]);
+        assert_eq!(set.gaps(-2..=40), vec![-2..=-1, 5..=22, 31..=40]);
+        assert_eq!(set.coverage(3..=25), 5);
+    }
+
+    #[test]
+    fn handles_empty_ranges_and_the_edges() {
+        let mut set = IntervalSet::from_iter([0..=10]);
+        #[allow(clippy::reversed_empty_ranges)]
+        let empty = 5..=4;
+        assert_eq!(set.coverage(empty.clone()), 0);
+        assert_eq!(set.gaps(empty), vec![]);
+
+        set.insert(i64::MIN..=i64::MIN + 5);
+        set.insert(i64::MAX - 5..=i64::MAX);
+        set.remove(i64::MIN..=i64::MIN + 2);
+        set.remove(i64::MAX - 2..=i64::MAX);
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            vec![
+                i64::MIN + 3..=i64::MIN + 5,
+                0..=10,
+                i64::MAX - 5..=i64::MAX - 3
+            ]
+        );
+
+        set.remove(i64::MIN..=i64::MAX);
+        assert!(set.is_empty());
+    }
+}
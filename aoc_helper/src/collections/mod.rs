@@ -1,5 +1,12 @@
 mod directional_collection;
+mod indexed_ring;
+mod interval_set;
 mod priority_queue;
+mod sparse_grid;
 
 pub use directional_collection::DirectionalCollection;
-pub use priority_queue::PriorityQueue;
\ No newline at end of file
+pub use indexed_ring::{IndexedRing, RingHandle};
+pub use interval_set::IntervalSet;
+pub use priority_queue::PriorityQueue;
+pub use sparse_grid::SparseGrid;
+
@@ -19,12 +19,12 @@ impl<K, T> PriorityQueue<K, T> {
     }
 
     pub fn pop_lowest(&mut self) -> Option<T> where K: Ord + Hash + Copy{
-        let Some(lowest_key) = self.bins.keys().min() else{return None};
+        let lowest_key = self.bins.keys().min()?;
         self.pop(*lowest_key)
     }
 
     pub fn pop_highest(&mut self) -> Option<T> where K: Ord + Hash + Copy{
-        let Some(highest_key) = self.bins.keys().max() else{return None};
+        let highest_key = self.bins.keys().max()?;
         self.pop(*highest_key)
     }
 
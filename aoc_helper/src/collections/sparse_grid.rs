@@ -0,0 +1,126 @@
+use std::{collections::HashSet, fmt::Display};
+
+use crate::vectors::Vec2D;
+
+/// The occupied cells of an unbounded grid, for when most of the grid is empty
+/// or it keeps growing in every direction
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SparseGrid {
+    cells: HashSet<Vec2D>,
+}
+
+impl SparseGrid {
+    pub fn new() -> Self {
+        Self {
+            cells: HashSet::new(),
+        }
+    }
+
+    /// Every `occupied` character in the text is a cell, the first character is at (0, 0)
+    pub fn from_text(text: &str, occupied: char) -> Self {
+        text.lines()
+            .enumerate()
+            .flat_map(|(y, line)| {
+                line.chars()
+                    .enumerate()
+                    .filter(move |(_, c)| *c == occupied)
+                    .map(move |(x, _)| Vec2D::new(x as i32, y as i32))
+            })
+            .collect()
+    }
+
+    /// Returns false when the cell was already occupied
+    pub fn insert(&mut self, cell: Vec2D) -> bool {
+        self.cells.insert(cell)
+    }
+
+    /// Returns false when the cell was not occupied
+    pub fn remove(&mut self, cell: Vec2D) -> bool {
+        self.cells.remove(&cell)
+    }
+
+    pub fn contains(&self, cell: Vec2D) -> bool {
+        self.cells.contains(&cell)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Vec2D> + '_ {
+        self.cells.iter().copied()
+    }
+
+    /// The top left and bottom right corner of the smallest rectangle around all cells
+    pub fn bounds(&self) -> Option<(Vec2D, Vec2D)> {
+        let mut cells = self.iter();
+        let first = cells.next()?;
+
+        Some(cells.fold((first, first), |(min, max), cell| {
+            (
+                Vec2D::new(min.x.min(cell.x), min.y.min(cell.y)),
+                Vec2D::new(max.x.max(cell.x), max.y.max(cell.y)),
+            )
+        }))
+    }
+
+    /// The number of empty cells in the smallest rectangle around all cells
+    pub fn empty_in_bounds(&self) -> usize {
+        match self.bounds() {
+            None => 0,
+            Some((min, max)) => {
+                let area = (max.x - min.x + 1) as usize * (max.y - min.y + 1) as usize;
+                area - self.len()
+            }
+        }
+    }
+}
+
+impl FromIterator<Vec2D> for SparseGrid {
+    fn from_iter<I: IntoIterator<Item = Vec2D>>(iter: I) -> Self {
+        Self {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Draws the rectangle around all cells, `#` for occupied and `.` for empty cells
+impl Display for SparseGrid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some((min, max)) = self.bounds() else {
+            return Ok(());
+        };
+
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                match self.contains(Vec2D::new(x, y)) {
+                    true => write!(f, "#")?,
+                    false => write!(f, ".")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_and_draws_cells() {
+        let grid = SparseGrid::from_text("..#\n#..\n...\n", '#');
+
+        assert_eq!(grid.len(), 2);
+        assert!(grid.contains(Vec2D::new(2, 0)));
+        assert_eq!(grid.bounds(), Some((Vec2D::new(0, 0), Vec2D::new(2, 1))));
+        assert_eq!(grid.empty_in_bounds(), 4);
+        assert_eq!(grid.to_string(), "..#\n#..\n");
+        assert_eq!(SparseGrid::new().to_string(), "");
+    }
+}
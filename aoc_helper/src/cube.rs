@@ -0,0 +1,277 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::vectors::Vec2D;
+
+type Vec3D = [i32; 3];
+
+/// The six faces of a cube, unfolded onto a map where spaces are not part of the cube
+/// Works for any of the 11 nets, the folding is worked out from the map itself
+pub struct CubeNet {
+    size: i32,
+    faces: Vec<Face>,
+}
+
+/// A face of the net, with the directions its map x and y axes and its outside point to in 3D
+#[derive(Clone, Copy, Debug)]
+struct Face {
+    tile: Vec2D,
+    normal: Vec3D,
+    right: Vec3D,
+    down: Vec3D,
+}
+
+impl CubeNet {
+    pub fn from_map<S: AsRef<str>>(map: &[S]) -> Self {
+        let cells = map
+            .iter()
+            .flat_map(|l| l.as_ref().chars())
+            .filter(|c| *c != ' ')
+            .count() as i32;
+
+        let size = (1..).find(|s| 6 * s * s >= cells).unwrap();
+        assert_eq!(6 * size * size, cells, "Map is not a cube net");
+
+        let is_face = |tile: Vec2D| {
+            let corner = tile * size;
+            corner.x >= 0
+                && corner.y >= 0
+                && map
+                    .get(corner.y as usize)
+                    .and_then(|l| l.as_ref().chars().nth(corner.x as usize))
+                    .is_some_and(|c| c != ' ')
+        };
+
+        let height = map.len() as i32 / size;
+        let width = map.iter().map(|l| l.as_ref().chars().count()).max().unwrap_or(0) as i32 / size;
+        let first = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Vec2D::new(x, y)))
+            .find(|tile| is_face(*tile))
+            .expect("Map is empty");
+
+        let mut faces: HashMap<Vec2D, Face> = HashMap::new();
+        let mut queue = VecDeque::from([Face {
+            tile: first,
+            normal: [0, 0, 1],
+            right: [1, 0, 0],
+            down: [0, 1, 0],
+        }]);
+
+        while let Some(face) = queue.pop_front() {
+            if faces.contains_key(&face.tile) {
+                continue;
+            }
+            faces.insert(face.tile, face);
+
+            for direction in crate::navigation::get_adjecent_directions() {
+                let tile = face.tile + direction;
+                if is_face(tile) && !faces.contains_key(&tile) {
+                    queue.push_back(face.fold(direction));
+                }
+            }
+        }
+
+        let faces: Vec<Face> = faces.into_values().collect();
+
+        let all_sides_covered = faces
+            .iter()
+            .all(|a| faces.iter().filter(|b| a.normal == b.normal).count() == 1);
+        assert!(faces.len() == 6 && all_sides_covered, "Map is not a cube net");
+
+        Self { size, faces }
+    }
+
+    /// The width and height of a single face
+    pub fn face_size(&self) -> i32 {
+        self.size
+    }
+
+    fn face_at(&self, position: Vec2D) -> Option<&Face> {
+        let tile = Vec2D::new(position.x.div_euclid(self.size), position.y.div_euclid(self.size));
+        self.faces.iter().find(|f| f.tile == tile)
+    }
+
+    /// Moves one step from `position` in `direction`, continuing on the adjacent face of the cube
+    /// when the step goes over the edge of the current face
+    /// Returns the new position and the direction to continue in, which changes when walking
+    /// over an edge that is not connected on the map
+    pub fn wrap(&self, position: Vec2D, direction: Vec2D) -> (Vec2D, Vec2D) {
+        let face = self.face_at(position).expect("Position is not on the cube");
+
+        let next = position + direction;
+        if self.face_at(next).is_some_and(|f| f.tile == face.tile) {
+            return (next, direction);
+        }
+
+        let size = self.size;
+        let exit = add(scale(face.right, direction.x), scale(face.down, direction.y));
+        let target = self
+            .faces
+            .iter()
+            .find(|f| f.normal == exit)
+            .expect("Direction should be one of the four adjecent directions");
+
+        // cell centres in 3D on a cube from -size to size, so they stay integers
+        let local = position - face.tile * size;
+        let point = add(
+            add(scale(face.normal, size), scale(face.right, 2 * local.x + 1 - size)),
+            scale(face.down, 2 * local.y + 1 - size),
+        );
+        let point = add(point, add(exit, scale(face.normal, -1)));
+
+        let local = Vec2D::new(
+            (dot(point, target.right) + size - 1) / 2,
+            (dot(point, target.down) + size - 1) / 2,
+        );
+
+        let inwards = scale(face.normal, -1);
+        let direction = Vec2D::new(dot(inwards, target.right), dot(inwards, target.down));
+
+        (target.tile * size + local, direction)
+    }
+}
+
+impl Face {
+    /// The face that is next to this one on the map in `direction`, folded over the shared edge
+    fn fold(&self, direction: Vec2D) -> Face {
+        let normal = add(scale(self.right, direction.x), scale(self.down, direction.y));
+        let right = if direction.x == 0 {
+            self.right
+        } else {
+            scale(self.normal, -direction.x)
+        };
+        let down = if direction.y == 0 {
+            self.down
+        } else {
+            scale(self.normal, -direction.y)
+        };
+
+        Face {
+            tile: self.tile + direction,
+            normal,
+            right,
+            down,
+        }
+    }
+}
+
+fn add(a: Vec3D, b: Vec3D) -> Vec3D {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn scale(a: Vec3D, factor: i32) -> Vec3D {
+    [a[0] * factor, a[1] * factor, a[2] * factor]
+}
+
+fn dot(a: Vec3D, b: Vec3D) -> i32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::navigation::get_adjecent_directions;
+    use std::collections::HashSet;
+
+    /// Blows up a net of tiles to a map where every face is `size` wide
+    fn to_map(net: &[&str], size: usize) -> Vec<String> {
+        net.iter()
+            .flat_map(|row| {
+                let line: String = row
+                    .chars()
+                    .flat_map(|c| std::iter::repeat_n(if c == '#' { '.' } else { ' ' }, size))
+                    .collect();
+                std::iter::repeat_n(line, size)
+            })
+            .collect()
+    }
+
+    /// Walks over every edge of every face, and checks the way back leads to the same place
+    fn assert_edges_glue(map: &[String]) {
+        let cube = CubeNet::from_map(map);
+        let is_on_cube = |p: Vec2D| {
+            p.x >= 0
+                && p.y >= 0
+                && map
+                    .get(p.y as usize)
+                    .and_then(|l| l.chars().nth(p.x as usize))
+                    .is_some_and(|c| c != ' ')
+        };
+
+        let mut targets = HashSet::new();
+        let mut count = 0;
+
+        for (y, line) in map.iter().enumerate() {
+            for (x, _) in line.chars().enumerate().filter(|(_, c)| *c != ' ') {
+                let position = Vec2D::new(x as i32, y as i32);
+                for direction in get_adjecent_directions() {
+                    let (next, next_direction) = cube.wrap(position, direction);
+                    assert!(is_on_cube(next), "{} {} left the cube", position, direction);
+
+                    let back = cube.wrap(next, next_direction * -1);
+                    assert_eq!(back, (position, direction * -1));
+
+                    targets.insert((next, next_direction));
+                    count += 1;
+                }
+            }
+        }
+
+        assert_eq!(targets.len(), count);
+    }
+
+    const NETS: [&[&str]; 11] = [
+        &["#...", "####", "#..."],
+        &["#...", "####", ".#.."],
+        &["#...", "####", "..#."],
+        &["#...", "####", "...#"],
+        &[".#..", "####", ".#.."],
+        &[".#..", "####", "..#."],
+        &["##..", ".###", ".#.."],
+        &["##..", ".###", "..#."],
+        &["##..", ".###", "...#"],
+        &["##..", ".##.", "..##"],
+        &["###..", "..###"],
+    ];
+
+    #[test]
+    fn all_nets_are_glued_together() {
+        for net in NETS {
+            assert_edges_glue(&to_map(net, 1));
+            assert_edges_glue(&to_map(net, 3));
+        }
+    }
+
+    #[test]
+    fn wraps_test_layout() {
+        let map = to_map(&["..#", "###", "..##"], 4);
+        assert_edges_glue(&map);
+
+        let cube = CubeNet::from_map(&map);
+        assert_eq!(cube.face_size(), 4);
+        assert_eq!(
+            cube.wrap(Vec2D::new(11, 5), Vec2D::new(1, 0)),
+            (Vec2D::new(14, 8), Vec2D::new(0, 1))
+        );
+        assert_eq!(
+            cube.wrap(Vec2D::new(10, 11), Vec2D::new(0, 1)),
+            (Vec2D::new(1, 7), Vec2D::new(0, -1))
+        );
+    }
+
+    #[test]
+    fn wraps_input_layout() {
+        let map = to_map(&[".##", ".#.", "##.", "#.."], 50);
+        assert_edges_glue(&map);
+
+        let cube = CubeNet::from_map(&map);
+        assert_eq!(cube.face_size(), 50);
+        assert_eq!(
+            cube.wrap(Vec2D::new(50, 10), Vec2D::new(-1, 0)),
+            (Vec2D::new(0, 139), Vec2D::new(1, 0))
+        );
+        assert_eq!(
+            cube.wrap(Vec2D::new(60, 0), Vec2D::new(0, -1)),
+            (Vec2D::new(0, 160), Vec2D::new(1, 0))
+        );
+    }
+}
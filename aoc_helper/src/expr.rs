@@ -0,0 +1,356 @@
+use std::fmt::Display;
+
+use crate::math;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl Operator {
+    pub fn from_symbol(symbol: &str) -> Option<Self> {
+        match symbol {
+            "+" => Some(Operator::Add),
+            "-" => Some(Operator::Subtract),
+            "*" => Some(Operator::Multiply),
+            "/" => Some(Operator::Divide),
+            _ => None,
+        }
+    }
+
+    pub fn symbol(&self) -> char {
+        match self {
+            Operator::Add => '+',
+            Operator::Subtract => '-',
+            Operator::Multiply => '*',
+            Operator::Divide => '/',
+        }
+    }
+
+    pub fn apply(&self, lhs: Rational, rhs: Rational) -> Result<Rational, ExprError> {
+        match self {
+            Operator::Add => lhs.checked_add(rhs),
+            Operator::Subtract => lhs.checked_sub(rhs),
+            Operator::Multiply => lhs.checked_mul(rhs),
+            Operator::Divide => lhs.checked_div(rhs),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExprError {
+    DivisionByZero,
+    Overflow,
+    /// A variable without a value was found while evaluating
+    UnknownVariable(String),
+    /// The variable to solve for appears on both sides of an operator
+    NonLinear,
+    /// The variable to solve for is not in the expression
+    VariableNotFound(String),
+    /// Zero or infinitely many values satisfy the equation, e.g. x * 0 = 5 or x * 0 = 0
+    NoUniqueSolution,
+}
+
+impl Display for ExprError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExprError::DivisionByZero => write!(f, "division by zero"),
+            ExprError::Overflow => write!(f, "arithmetic overflow"),
+            ExprError::UnknownVariable(name) => write!(f, "unknown variable {}", name),
+            ExprError::NonLinear => write!(f, "expression is not linear in the variable"),
+            ExprError::VariableNotFound(name) => write!(f, "variable {} not found", name),
+            ExprError::NoUniqueSolution => write!(f, "equation has no unique solution"),
+        }
+    }
+}
+
+impl std::error::Error for ExprError {}
+
+/// An exact fraction, always stored with a positive denominator and no common factors
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Rational {
+    pub fn new(numerator: i128, denominator: i128) -> Result<Self, ExprError> {
+        if denominator == 0 {
+            return Err(ExprError::DivisionByZero);
+        }
+
+        let divisor = math::gcd(numerator, denominator) * denominator.signum();
+        Ok(Self {
+            numerator: numerator / divisor,
+            denominator: denominator / divisor,
+        })
+    }
+
+    pub fn numerator(&self) -> i128 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> i128 {
+        self.denominator
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.numerator == 0
+    }
+
+    /// The value as a whole number, if it is one and fits
+    pub fn to_integer(&self) -> Option<i64> {
+        if self.denominator != 1 {
+            return None;
+        }
+        self.numerator.try_into().ok()
+    }
+
+    pub fn checked_add(self, rhs: Rational) -> Result<Rational, ExprError> {
+        let numerator = mul(self.numerator, rhs.denominator)?
+            .checked_add(mul(rhs.numerator, self.denominator)?)
+            .ok_or(ExprError::Overflow)?;
+        Rational::new(numerator, mul(self.denominator, rhs.denominator)?)
+    }
+
+    pub fn checked_sub(self, rhs: Rational) -> Result<Rational, ExprError> {
+        self.checked_add(Rational {
+            numerator: rhs.numerator.checked_neg().ok_or(ExprError::Overflow)?,
+            denominator: rhs.denominator,
+        })
+    }
+
+    pub fn checked_mul(self, rhs: Rational) -> Result<Rational, ExprError> {
+        Rational::new(
+            mul(self.numerator, rhs.numerator)?,
+            mul(self.denominator, rhs.denominator)?,
+        )
+    }
+
+    pub fn checked_div(self, rhs: Rational) -> Result<Rational, ExprError> {
+        Rational::new(
+            mul(self.numerator, rhs.denominator)?,
+            mul(self.denominator, rhs.numerator)?,
+        )
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(value: i64) -> Self {
+        Self {
+            numerator: value as i128,
+            denominator: 1,
+        }
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.denominator == 1 {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+fn mul(a: i128, b: i128) -> Result<i128, ExprError> {
+    a.checked_mul(b).ok_or(ExprError::Overflow)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expr {
+    Constant(Rational),
+    Variable(String),
+    Binary(Box<Expr>, Operator, Box<Expr>),
+}
+
+impl Expr {
+    pub fn constant(value: i64) -> Self {
+        Expr::Constant(value.into())
+    }
+
+    pub fn variable(name: &str) -> Self {
+        Expr::Variable(name.to_owned())
+    }
+
+    pub fn binary(lhs: Expr, operator: Operator, rhs: Expr) -> Self {
+        Expr::Binary(Box::new(lhs), operator, Box::new(rhs))
+    }
+
+    pub fn contains_variable(&self, name: &str) -> bool {
+        match self {
+            Expr::Constant(_) => false,
+            Expr::Variable(variable) => variable == name,
+            Expr::Binary(lhs, _, rhs) => lhs.contains_variable(name) || rhs.contains_variable(name),
+        }
+    }
+
+    /// Calculates every part that does not depend on a variable
+    pub fn fold(&self) -> Result<Expr, ExprError> {
+        match self {
+            Expr::Binary(lhs, operator, rhs) => match (lhs.fold()?, rhs.fold()?) {
+                (Expr::Constant(lhs), Expr::Constant(rhs)) => {
+                    Ok(Expr::Constant(operator.apply(lhs, rhs)?))
+                }
+                (lhs, rhs) => Ok(Expr::binary(lhs, *operator, rhs)),
+            },
+            expr => Ok(expr.clone()),
+        }
+    }
+
+    /// Calculates the value, looking up variables with `variables`
+    pub fn evaluate<F>(&self, variables: &F) -> Result<Rational, ExprError>
+    where
+        F: Fn(&str) -> Option<Rational>,
+    {
+        match self {
+            Expr::Constant(value) => Ok(*value),
+            Expr::Variable(name) => {
+                variables(name).ok_or_else(|| ExprError::UnknownVariable(name.clone()))
+            }
+            Expr::Binary(lhs, operator, rhs) => {
+                operator.apply(lhs.evaluate(variables)?, rhs.evaluate(variables)?)
+            }
+        }
+    }
+
+    /// Finds the value of `variable` for which the expression equals `target`
+    /// The variable may appear only once, on either side of each operator
+    pub fn solve_for(&self, variable: &str, target: Rational) -> Result<Rational, ExprError> {
+        if !self.contains_variable(variable) {
+            return Err(ExprError::VariableNotFound(variable.to_owned()));
+        }
+
+        let mut expr = self.fold()?;
+        let mut target = target;
+
+        loop {
+            match expr {
+                Expr::Variable(_) => return Ok(target),
+                Expr::Constant(_) => unreachable!("the variable was in the expression"),
+                Expr::Binary(lhs, operator, rhs) => {
+                    let unknown_left = lhs.contains_variable(variable);
+                    let unknown_right = rhs.contains_variable(variable);
+
+                    (expr, target) = match (unknown_left, unknown_right) {
+                        (true, true) => return Err(ExprError::NonLinear),
+                        (true, false) => {
+                            let known = rhs.evaluate(&|_| None)?;
+                            (*lhs, solve_left(operator, target, known)?)
+                        }
+                        (false, true) => {
+                            let known = lhs.evaluate(&|_| None)?;
+                            (*rhs, solve_right(operator, target, known)?)
+                        }
+                        (false, false) => unreachable!("the variable was in the expression"),
+                    };
+                }
+            }
+        }
+    }
+}
+
+/// Solves `x operator known = target` for x
+fn solve_left(operator: Operator, target: Rational, known: Rational) -> Result<Rational, ExprError> {
+    match operator {
+        Operator::Add => target.checked_sub(known),
+        Operator::Subtract => target.checked_add(known),
+        Operator::Multiply if known.is_zero() => Err(ExprError::NoUniqueSolution),
+        Operator::Multiply => target.checked_div(known),
+        Operator::Divide if known.is_zero() => Err(ExprError::DivisionByZero),
+        Operator::Divide => target.checked_mul(known),
+    }
+}
+
+/// Solves `known operator x = target` for x
+fn solve_right(operator: Operator, target: Rational, known: Rational) -> Result<Rational, ExprError> {
+    match operator {
+        Operator::Add => target.checked_sub(known),
+        Operator::Subtract => known.checked_sub(target),
+        Operator::Multiply if known.is_zero() => Err(ExprError::NoUniqueSolution),
+        Operator::Multiply => target.checked_div(known),
+        Operator::Divide if target.is_zero() => Err(ExprError::NoUniqueSolution),
+        Operator::Divide => known.checked_div(target),
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expr::Constant(value) => write!(f, "{}", value),
+            Expr::Variable(name) => write!(f, "{}", name),
+            Expr::Binary(lhs, operator, rhs) => write!(f, "({} {} {})", lhs, operator.symbol(), rhs),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn x() -> Expr {
+        Expr::variable("x")
+    }
+
+    fn c(value: i64) -> Expr {
+        Expr::constant(value)
+    }
+
+    #[test]
+    fn folds_and_evaluates() {
+        // (4 + 2) * (x - 3 / 2)
+        let expr = Expr::binary(
+            Expr::binary(c(4), Operator::Add, c(2)),
+            Operator::Multiply,
+            Expr::binary(x(), Operator::Subtract, Expr::binary(c(3), Operator::Divide, c(2))),
+        );
+
+        assert_eq!(expr.fold().unwrap().to_string(), "(6 * (x - 3/2))");
+        assert_eq!(expr.evaluate(&|_| Some(5.into())), Ok(21.into()));
+        assert_eq!(
+            expr.evaluate(&|_| None),
+            Err(ExprError::UnknownVariable("x".to_owned()))
+        );
+        assert_eq!(
+            Expr::binary(c(1), Operator::Divide, c(0)).fold(),
+            Err(ExprError::DivisionByZero)
+        );
+    }
+
+    #[test]
+    fn solves_with_the_variable_on_either_side() {
+        // 10 - (x / 4) = 7
+        let expr = Expr::binary(c(10), Operator::Subtract, Expr::binary(x(), Operator::Divide, c(4)));
+        assert_eq!(expr.solve_for("x", 7.into()), Ok(12.into()));
+
+        // 12 / (2 * x + 1) = 4
+        let expr = Expr::binary(
+            c(12),
+            Operator::Divide,
+            Expr::binary(Expr::binary(c(2), Operator::Multiply, x()), Operator::Add, c(1)),
+        );
+        assert_eq!(expr.solve_for("x", 4.into()), Ok(1.into()));
+        assert_eq!(expr.solve_for("x", 5.into()), Rational::new(7, 10));
+    }
+
+    #[test]
+    fn reports_unsolvable_equations() {
+        let square = Expr::binary(x(), Operator::Multiply, x());
+        assert_eq!(square.solve_for("x", 4.into()), Err(ExprError::NonLinear));
+
+        let times_zero = Expr::binary(x(), Operator::Multiply, c(0));
+        assert_eq!(times_zero.solve_for("x", 4.into()), Err(ExprError::NoUniqueSolution));
+
+        let by_zero = Expr::binary(x(), Operator::Divide, Expr::binary(c(2), Operator::Subtract, c(2)));
+        assert_eq!(by_zero.solve_for("x", 4.into()), Err(ExprError::DivisionByZero));
+
+        assert_eq!(
+            c(3).solve_for("x", 4.into()),
+            Err(ExprError::VariableNotFound("x".to_owned()))
+        );
+    }
+}
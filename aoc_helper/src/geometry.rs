@@ -0,0 +1,197 @@
+use std::ops::RangeInclusive;
+
+use crate::vectors::Vec2D;
+
+/// All cells within a manhattan distance of the centre, e.g. the area a sensor can see
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Diamond {
+    pub centre: Vec2D,
+    pub radius: i32,
+}
+
+/// A diagonal line on the grid
+/// Rising lines hold all cells with x - y equal to the value, falling lines those with x + y
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiagonalLine {
+    Rising(i64),
+    Falling(i64),
+}
+
+impl Diamond {
+    pub fn new(centre: Vec2D, radius: i32) -> Self {
+        Self { centre, radius }
+    }
+
+    /// The smallest diamond around the centre that still contains the point
+    pub fn through(centre: Vec2D, point: Vec2D) -> Self {
+        Self::new(centre, centre.manhattan_distance(point) as i32)
+    }
+
+    /// A diamond with a negative radius is empty
+    pub fn contains(&self, point: Vec2D) -> bool {
+        u32::try_from(self.radius)
+            .is_ok_and(|radius| self.centre.manhattan_distance(point) <= radius)
+    }
+
+    /// The x values of the diamond on row `y`
+    pub fn row_intersection(&self, y: i32) -> Option<RangeInclusive<i64>> {
+        let half_width = self.radius as i64 - (self.centre.y as i64 - y as i64).abs();
+        if half_width < 0 {
+            return None;
+        }
+
+        let x = self.centre.x as i64;
+        Some(x - half_width..=x + half_width)
+    }
+
+    /// The four lines the border of the diamond lies on
+    pub fn edge_lines(&self) -> [DiagonalLine; 4] {
+        let (u, v) = to_rotated(self.centre);
+        let radius = self.radius as i64;
+
+        [
+            DiagonalLine::Falling(u - radius),
+            DiagonalLine::Falling(u + radius),
+            DiagonalLine::Rising(v - radius),
+            DiagonalLine::Rising(v + radius),
+        ]
+    }
+
+    /// Same as `edge_lines`, but for the cells just outside the diamond
+    pub fn outer_edge_lines(&self) -> [DiagonalLine; 4] {
+        Diamond::new(self.centre, self.radius + 1).edge_lines()
+    }
+}
+
+impl DiagonalLine {
+    /// The cells where a rising and a falling line cross
+    /// When the lines cross in between cells, the two cells closest to the crossing are returned
+    pub fn intersections(&self, other: &DiagonalLine) -> Vec<Vec2D> {
+        let (rising, falling) = match (self, other) {
+            (DiagonalLine::Rising(r), DiagonalLine::Falling(f))
+            | (DiagonalLine::Falling(f), DiagonalLine::Rising(r)) => (*r, *f),
+            _ => return vec![],
+        };
+
+        if (rising + falling) % 2 == 0 {
+            from_rotated(falling, rising).into_iter().collect()
+        } else {
+            [falling - 1, falling + 1]
+                .into_iter()
+                .filter_map(|u| from_rotated(u, rising))
+                .collect()
+        }
+    }
+
+    /// The cell of the line in column `x`
+    pub fn at_x(&self, x: i64) -> Option<Vec2D> {
+        let y = match self {
+            DiagonalLine::Rising(v) => x - v,
+            DiagonalLine::Falling(u) => u - x,
+        };
+        Some(Vec2D::new(x.try_into().ok()?, y.try_into().ok()?))
+    }
+
+    /// The cell of the line in row `y`
+    pub fn at_y(&self, y: i64) -> Option<Vec2D> {
+        let x = match self {
+            DiagonalLine::Rising(v) => v + y,
+            DiagonalLine::Falling(u) => u - y,
+        };
+        Some(Vec2D::new(x.try_into().ok()?, y.try_into().ok()?))
+    }
+}
+
+/// Rotates the grid 45 degrees to (x + y, x - y), diamonds become axis aligned squares there
+pub fn to_rotated(point: Vec2D) -> (i64, i64) {
+    let (x, y) = (point.x as i64, point.y as i64);
+    (x + y, x - y)
+}
+
+/// The inverse of `to_rotated`, only half of the rotated coordinates are an actual cell
+pub fn from_rotated(u: i64, v: i64) -> Option<Vec2D> {
+    if (u + v) % 2 != 0 {
+        return None;
+    }
+
+    let x = (u + v) / 2;
+    let y = (u - v) / 2;
+    Some(Vec2D::new(x.try_into().ok()?, y.try_into().ok()?))
+}
+
+/// Finds a cell in the box from `min` to `max` (inclusive) that is in none of the diamonds
+/// Only the corners of the uncovered area are checked, being crossings of the edges
+/// just outside the diamonds and the box, so this is meant for small uncovered areas
+pub fn find_uncovered(diamonds: &[Diamond], min: Vec2D, max: Vec2D) -> Option<Vec2D> {
+    let lines: Vec<DiagonalLine> = diamonds.iter().flat_map(|d| d.outer_edge_lines()).collect();
+
+    let corners = [
+        min,
+        Vec2D::new(max.x, min.y),
+        Vec2D::new(min.x, max.y),
+        max,
+    ];
+
+    let crossings = lines
+        .iter()
+        .enumerate()
+        .flat_map(|(i, a)| lines[i + 1..].iter().flat_map(|b| a.intersections(b)));
+
+    let box_crossings = lines.iter().flat_map(|line| {
+        [
+            line.at_x(min.x as i64),
+            line.at_x(max.x as i64),
+            line.at_y(min.y as i64),
+            line.at_y(max.y as i64),
+        ]
+        .into_iter()
+        .flatten()
+    });
+
+    corners
+        .into_iter()
+        .chain(box_crossings)
+        .chain(crossings)
+        .filter(|p| p.x >= min.x && p.y >= min.y && p.x <= max.x && p.y <= max.y)
+        .find(|p| diamonds.iter().all(|d| !d.contains(*p)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn row_intersection_and_rotation() {
+        let diamond = Diamond::through(Vec2D::new(8, 7), Vec2D::new(2, 10));
+        assert_eq!(diamond.radius, 9);
+        assert_eq!(diamond.row_intersection(10), Some(2..=14));
+        assert_eq!(diamond.row_intersection(16), Some(8..=8));
+        assert_eq!(diamond.row_intersection(17), None);
+        assert!(diamond.contains(Vec2D::new(17, 7)));
+        assert!(!Diamond::new(Vec2D::new(0, 0), -1).contains(Vec2D::new(0, 0)));
+
+        let point = Vec2D::new(3, -5);
+        let (u, v) = to_rotated(point);
+        assert_eq!(from_rotated(u, v), Some(point));
+        assert_eq!(from_rotated(u + 1, v), None);
+    }
+
+    #[test]
+    fn finds_the_single_uncovered_cell() {
+        let diamonds = [
+            Diamond::new(Vec2D::new(0, 0), 5),
+            Diamond::new(Vec2D::new(6, 0), 5),
+            Diamond::new(Vec2D::new(0, 6), 5),
+            Diamond::new(Vec2D::new(6, 6), 5),
+        ];
+
+        assert_eq!(
+            find_uncovered(&diamonds, Vec2D::new(0, 0), Vec2D::new(6, 6)),
+            Some(Vec2D::new(3, 3))
+        );
+        assert_eq!(
+            find_uncovered(&diamonds[..1], Vec2D::new(0, 0), Vec2D::new(2, 2)),
+            None
+        );
+    }
+}
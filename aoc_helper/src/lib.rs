@@ -1,5 +1,37 @@
 pub mod math;
 pub mod vectors;
 pub mod collections;
+pub mod automaton;
 pub mod navigation;
-pub mod runner;
\ No newline at end of file
+pub mod geometry;
+pub mod cube;
+pub mod expr;
+pub mod nested;
+pub mod numeral;
+pub mod search;
+pub mod runner;
+pub mod progress;
+pub mod all_days;
+pub mod prelude;
+
+/// Old path of `Vec2D`, from before the vectors module existed
+#[deprecated(note = "use `aoc_helper::vectors::Vec2D` or `aoc_helper::prelude`")]
+pub mod vec2d {
+    pub use crate::vectors::Vec2D;
+}
+
+/// Old path of `UVec2D`, from before the vectors module existed
+#[deprecated(note = "use `aoc_helper::vectors::UVec2D` or `aoc_helper::prelude`")]
+pub mod uvec2d {
+    pub use crate::vectors::UVec2D;
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    #[allow(deprecated)]
+    fn old_paths_still_resolve() {
+        assert_eq!(crate::vec2d::Vec2D::new(1, 2), crate::prelude::Vec2D::new(1, 2));
+        assert!(crate::uvec2d::UVec2D::new(1, 2) == crate::prelude::UVec2D::new(1, 2));
+    }
+}
@@ -1,3 +1,256 @@
-pub fn positive_mod(value: i32, modulus: i32) -> i32 {
-    ((value % modulus) + modulus) % modulus
+use std::fmt::{Debug, Display};
+
+/// The integer types the functions in this module work with
+pub trait Integer: Copy + Ord + Debug + Display {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_div(self, rhs: Self) -> Option<Self>;
+    fn checked_rem(self, rhs: Self) -> Option<Self>;
+    fn checked_rem_euclid(self, rhs: Self) -> Option<Self>;
+    fn checked_abs(self) -> Option<Self>;
+}
+
+/// Integers that can be negative, needed for the coefficients of the extended Euclid algorithm
+pub trait SignedInteger: Integer {
+    fn checked_neg(self) -> Option<Self>;
+}
+
+macro_rules! integer {
+    ($($t:ty),*) => {
+        $(impl Integer for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_add(self, rhs)
+            }
+
+            fn checked_sub(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_sub(self, rhs)
+            }
+
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_mul(self, rhs)
+            }
+
+            fn checked_div(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_div(self, rhs)
+            }
+
+            fn checked_rem(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_rem(self, rhs)
+            }
+
+            fn checked_rem_euclid(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_rem_euclid(self, rhs)
+            }
+
+            #[allow(unused_comparisons)]
+            fn checked_abs(self) -> Option<Self> {
+                if self < 0 {
+                    <$t>::checked_sub(0, self)
+                } else {
+                    Some(self)
+                }
+            }
+        })*
+    };
+}
+
+macro_rules! signed_integer {
+    ($($t:ty),*) => {
+        $(impl SignedInteger for $t {
+            fn checked_neg(self) -> Option<Self> {
+                <$t>::checked_neg(self)
+            }
+        })*
+    };
+}
+
+integer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+signed_integer!(i8, i16, i32, i64, i128, isize);
+
+/// Returned by the checked functions when an intermediate value does not fit the type
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Overflow;
+
+/// The remainder that is never negative, e.g. -1 mod 5 is 4
+pub fn positive_mod<T: Integer>(value: T, modulus: T) -> T {
+    checked_positive_mod(value, modulus).expect("modulus should not be zero")
+}
+
+pub fn checked_positive_mod<T: Integer>(value: T, modulus: T) -> Option<T> {
+    value.checked_rem_euclid(modulus)
+}
+
+/// Greatest common divisor, always positive (or zero when both numbers are zero)
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    checked_gcd(a, b).expect("gcd overflowed")
+}
+
+pub fn checked_gcd<T: Integer>(a: T, b: T) -> Option<T> {
+    let (mut a, mut b) = (a, b);
+    while b != T::ZERO {
+        (a, b) = (b, a.checked_rem(b)?);
+    }
+    a.checked_abs()
+}
+
+/// Smallest number that both numbers divide, e.g. the period of two repeating cycles
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    checked_lcm(a, b).expect("lcm overflowed")
+}
+
+pub fn checked_lcm<T: Integer>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+    a.checked_div(checked_gcd(a, b)?)?
+        .checked_mul(b)?
+        .checked_abs()
+}
+
+/// Returns (g, x, y) with g = gcd(a, b) and a * x + b * y = g
+pub fn extended_gcd<T: SignedInteger>(a: T, b: T) -> (T, T, T) {
+    checked_extended_gcd(a, b).expect("extended gcd overflowed")
+}
+
+pub fn checked_extended_gcd<T: SignedInteger>(a: T, b: T) -> Option<(T, T, T)> {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (T::ONE, T::ZERO);
+    let (mut old_y, mut y) = (T::ZERO, T::ONE);
+
+    while r != T::ZERO {
+        let quotient = old_r.checked_div(r)?;
+        (old_r, r) = (r, old_r.checked_sub(quotient.checked_mul(r)?)?);
+        (old_x, x) = (x, old_x.checked_sub(quotient.checked_mul(x)?)?);
+        (old_y, y) = (y, old_y.checked_sub(quotient.checked_mul(y)?)?);
+    }
+
+    if old_r < T::ZERO {
+        return Some((old_r.checked_neg()?, old_x.checked_neg()?, old_y.checked_neg()?));
+    }
+    Some((old_r, old_x, old_y))
+}
+
+/// The x for which a * x mod m is 1, None when a and m have a common divisor
+/// or the modulus is not positive
+pub fn mod_inverse<T: SignedInteger>(a: T, modulus: T) -> Option<T> {
+    if modulus <= T::ZERO {
+        return None;
+    }
+    let (g, x, _) = extended_gcd(positive_mod(a, modulus), modulus);
+    if g != T::ONE {
+        return None;
+    }
+    Some(positive_mod(x, modulus))
+}
+
+/// base^exponent mod modulus, by squaring so large exponents are fine
+pub fn mod_pow<T: Integer>(base: T, exponent: u64, modulus: T) -> T {
+    checked_mod_pow(base, exponent, modulus).expect("mod pow overflowed, use a wider type")
+}
+
+pub fn checked_mod_pow<T: Integer>(base: T, exponent: u64, modulus: T) -> Option<T> {
+    let mut result = T::ONE.checked_rem_euclid(modulus)?;
+    let mut base = base.checked_rem_euclid(modulus)?;
+    let mut exponent = exponent;
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result.checked_mul(base)?.checked_rem_euclid(modulus)?;
+        }
+        base = base.checked_mul(base)?.checked_rem_euclid(modulus)?;
+        exponent >>= 1;
+    }
+
+    Some(result)
+}
+
+/// Finds x with x mod m = r for every (r, m), returns x and the combined modulus
+/// The moduli do not need to be coprime, None means there is no solution
+pub fn crt<T: SignedInteger>(congruences: &[(T, T)]) -> Option<(T, T)> {
+    checked_crt(congruences).expect("crt overflowed, use a wider type")
+}
+
+pub fn checked_crt<T: SignedInteger>(congruences: &[(T, T)]) -> Result<Option<(T, T)>, Overflow> {
+    let mut result = (T::ZERO, T::ONE);
+
+    for &(residue, modulus) in congruences {
+        let (r1, m1) = result;
+        let r2 = checked_positive_mod(residue, modulus).ok_or(Overflow)?;
+
+        let (g, p, _) = checked_extended_gcd(m1, modulus).ok_or(Overflow)?;
+        let difference = r2.checked_sub(r1).ok_or(Overflow)?;
+
+        if difference.checked_rem(g).ok_or(Overflow)? != T::ZERO {
+            return Ok(None);
+        }
+
+        let step = modulus.checked_div(g).ok_or(Overflow)?;
+        let combined = m1.checked_mul(step).ok_or(Overflow)?;
+        let k = (|| {
+            let quotient = checked_positive_mod(difference.checked_div(g)?, step)?;
+            checked_positive_mod(quotient.checked_mul(checked_positive_mod(p, step)?)?, step)
+        })()
+        .ok_or(Overflow)?;
+
+        let x = m1
+            .checked_mul(k)
+            .and_then(|v| v.checked_add(r1))
+            .and_then(|v| checked_positive_mod(v, combined))
+            .ok_or(Overflow)?;
+        result = (x, combined);
+    }
+
+    Ok(Some(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_and_lcm_for_any_width() {
+        assert_eq!(gcd(12u8, 18), 6);
+        assert_eq!(gcd(-12i64, 18), 6);
+        assert_eq!(gcd(0u32, 0), 0);
+        assert_eq!(lcm(4usize, 6), 12);
+        assert_eq!(lcm(-4i32, 6), 12);
+        assert_eq!(checked_lcm(200u8, 3), None);
+        assert_eq!(checked_gcd(i32::MIN, 0), None);
+    }
+
+    #[test]
+    fn modular_arithmetic() {
+        assert_eq!(positive_mod(-1i8, 5), 4);
+        assert_eq!(positive_mod(7u16, 5), 2);
+        assert_eq!(checked_positive_mod(7u16, 0), None);
+
+        let (g, x, y) = extended_gcd(240i32, 46);
+        assert_eq!((g, 240 * x + 46 * y), (2, 2));
+
+        assert_eq!(mod_inverse(3i64, 11), Some(4));
+        assert_eq!(mod_inverse(4i64, 8), None);
+        assert_eq!(mod_inverse(3i64, 0), None);
+        assert_eq!(mod_inverse(3i64, -11), None);
+
+        assert_eq!(mod_pow(4u32, 13, 497), 445);
+        assert_eq!(mod_pow(-2i32, 3, 5), 2);
+        assert_eq!(checked_mod_pow(u64::MAX - 1, 2, u64::MAX), None);
+        assert_eq!(mod_pow((u64::MAX - 1) as u128, 2, u64::MAX as u128), 1);
+    }
+
+    #[test]
+    fn chinese_remainder_theorem() {
+        assert_eq!(crt(&[(2i64, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(1i32, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt(&[(1i32, 4), (2, 6)]), None);
+        assert_eq!(crt::<i32>(&[]), Some((0, 1)));
+        assert_eq!(checked_crt(&[(1i8, 100), (2, 99)]), Err(Overflow));
+    }
 }
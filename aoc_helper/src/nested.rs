@@ -0,0 +1,479 @@
+use std::{cmp::Ordering, fmt::Display, str::FromStr};
+
+/// A value or a list of nested lists, written like `[1,[2,-3],[]]`
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NestedList<T> {
+    Value(T),
+    List(Vec<NestedList<T>>),
+}
+
+/// How a value and a list compare to each other, lists always compare element by element
+/// with the shorter list first when one is the start of the other
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Comparison {
+    /// The value is compared as a list holding only that value
+    #[default]
+    WrapValue,
+    /// Values always come before lists
+    ValuesFirst,
+    /// Lists always come before values
+    ListsFirst,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// Character position in the input where parsing failed
+    pub position: usize,
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl<T> NestedList<T> {
+    pub fn compare(&self, other: &Self, comparison: Comparison) -> Ordering
+    where
+        T: Ord,
+    {
+        match (self, other) {
+            (NestedList::Value(l), NestedList::Value(r)) => l.cmp(r),
+            (NestedList::List(l), NestedList::List(r)) => l
+                .iter()
+                .zip(r)
+                .map(|(l, r)| l.compare(r, comparison))
+                .find(|o| o.is_ne())
+                .unwrap_or_else(|| l.len().cmp(&r.len())),
+            (NestedList::Value(_), NestedList::List(r)) => match comparison {
+                Comparison::WrapValue => compare_wrapped(self, r, comparison),
+                Comparison::ValuesFirst => Ordering::Less,
+                Comparison::ListsFirst => Ordering::Greater,
+            },
+            (NestedList::List(_), NestedList::Value(_)) => {
+                other.compare(self, comparison).reverse()
+            }
+        }
+    }
+
+    /// Parses JSON arrays, with values in the JSON format of `T`
+    pub fn from_json(string: &str) -> Result<Self, ParseError>
+    where
+        T: JsonValue,
+    {
+        Parser::new(string, |token| {
+            T::from_json(token).ok_or("invalid JSON value".to_owned())
+        })
+        .parse_all()
+    }
+
+    pub fn to_json(&self) -> String
+    where
+        T: JsonValue,
+    {
+        match self {
+            NestedList::Value(value) => value.to_json(),
+            NestedList::List(list) => {
+                let items: Vec<String> = list.iter().map(|i| i.to_json()).collect();
+                format!("[{}]", items.join(","))
+            }
+        }
+    }
+}
+
+fn compare_wrapped<T: Ord>(
+    value: &NestedList<T>,
+    list: &[NestedList<T>],
+    comparison: Comparison,
+) -> Ordering {
+    match list.first() {
+        None => Ordering::Greater,
+        Some(first) => value
+            .compare(first, comparison)
+            .then_with(|| 1.cmp(&list.len())),
+    }
+}
+
+impl<T: Display> Display for NestedList<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NestedList::Value(value) => write!(f, "{}", value),
+            NestedList::List(list) => {
+                write!(f, "[")?;
+                for (i, item) in list.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+impl<T> FromStr for NestedList<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    type Err = ParseError;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        Parser::new(string, |token| {
+            T::from_str(token).map_err(|e| e.to_string())
+        })
+        .parse_all()
+    }
+}
+
+/// Values that can be written to and read from a single JSON value
+pub trait JsonValue: Sized {
+    fn to_json(&self) -> String;
+    fn from_json(token: &str) -> Option<Self>;
+}
+
+macro_rules! json_number {
+    ($($t:ty),*) => {
+        $(impl JsonValue for $t {
+            fn to_json(&self) -> String {
+                self.to_string()
+            }
+
+            fn from_json(token: &str) -> Option<Self> {
+                token.parse().ok()
+            }
+        })*
+    };
+}
+
+json_number!(i32, i64, u32, u64, usize);
+
+/// JSON has no NaN or infinities, they are written as `null` and never read back
+impl JsonValue for f64 {
+    fn to_json(&self) -> String {
+        if self.is_finite() {
+            self.to_string()
+        } else {
+            "null".to_owned()
+        }
+    }
+
+    fn from_json(token: &str) -> Option<Self> {
+        token.parse().ok().filter(|f: &f64| f.is_finite())
+    }
+}
+
+impl JsonValue for String {
+    fn to_json(&self) -> String {
+        let mut json = "\"".to_owned();
+        for c in self.chars() {
+            match c {
+                '"' => json += "\\\"",
+                '\\' => json += "\\\\",
+                '\n' => json += "\\n",
+                '\t' => json += "\\t",
+                '\r' => json += "\\r",
+                '\u{8}' => json += "\\b",
+                '\u{c}' => json += "\\f",
+                c if c < ' ' => json += &format!("\\u{:04x}", c as u32),
+                c => json.push(c),
+            }
+        }
+        json + "\""
+    }
+
+    fn from_json(token: &str) -> Option<Self> {
+        let inner = token.strip_prefix('"')?.strip_suffix('"')?;
+        let mut string = String::new();
+        let mut chars = inner.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next()? {
+                    'n' => string.push('\n'),
+                    't' => string.push('\t'),
+                    'r' => string.push('\r'),
+                    'b' => string.push('\u{8}'),
+                    'f' => string.push('\u{c}'),
+                    'u' => string.push(unicode_escape(&mut chars)?),
+                    c @ ('"' | '\\' | '/') => string.push(c),
+                    _ => return None,
+                },
+                c if c < ' ' => return None,
+                c => string.push(c),
+            }
+        }
+
+        Some(string)
+    }
+}
+
+/// Reads the hex digits after a `\u`, characters outside the basic plane are written as a
+/// surrogate pair of two escapes
+fn unicode_escape(chars: &mut std::str::Chars) -> Option<char> {
+    let high = code_unit(chars)?;
+    match high {
+        0xD800..=0xDBFF => {
+            if chars.next()? != '\\' || chars.next()? != 'u' {
+                return None;
+            }
+            let low = code_unit(chars)?;
+            if !(0xDC00..=0xDFFF).contains(&low) {
+                return None;
+            }
+            char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00))
+        }
+        _ => char::from_u32(high),
+    }
+}
+
+fn code_unit(chars: &mut std::str::Chars) -> Option<u32> {
+    let digits: String = chars.take(4).collect();
+    if digits.len() != 4 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    u32::from_str_radix(&digits, 16).ok()
+}
+
+struct Parser<F> {
+    chars: Vec<char>,
+    position: usize,
+    parse_value: F,
+}
+
+impl<T, F> Parser<F>
+where
+    F: Fn(&str) -> Result<T, String>,
+{
+    fn new(input: &str, parse_value: F) -> Self {
+        Self {
+            chars: input.chars().collect(),
+            position: 0,
+            parse_value,
+        }
+    }
+
+    fn parse_all(mut self) -> Result<NestedList<T>, ParseError> {
+        let list = self.parse_item()?;
+        self.skip_whitespace();
+
+        if self.position < self.chars.len() {
+            return Err(self.error("unexpected characters after the end"));
+        }
+        Ok(list)
+    }
+
+    fn parse_item(&mut self) -> Result<NestedList<T>, ParseError> {
+        self.skip_whitespace();
+
+        match self.peek() {
+            None => Err(self.error("unexpected end of input")),
+            Some('[') => self.parse_list(),
+            Some(']' | ',') => Err(self.error("expected a value")),
+            Some(_) => self.parse_value(),
+        }
+    }
+
+    fn parse_list(&mut self) -> Result<NestedList<T>, ParseError> {
+        self.position += 1;
+        let mut items = vec![];
+
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.position += 1;
+            return Ok(NestedList::List(items));
+        }
+
+        loop {
+            items.push(self.parse_item()?);
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(',') => self.position += 1,
+                Some(']') => {
+                    self.position += 1;
+                    return Ok(NestedList::List(items));
+                }
+                None => return Err(self.error("unexpected end of input, expected ']'")),
+                Some(_) => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<NestedList<T>, ParseError> {
+        let start = self.position;
+
+        if self.peek() == Some('"') {
+            self.position += 1;
+            loop {
+                match self.peek() {
+                    None => return Err(self.error("unterminated string")),
+                    Some('\\') => self.position += 2,
+                    Some('"') => {
+                        self.position += 1;
+                        break;
+                    }
+                    Some(_) => self.position += 1,
+                }
+            }
+        } else {
+            while self
+                .peek()
+                .is_some_and(|c| !matches!(c, '[' | ']' | ',') && !c.is_whitespace())
+            {
+                self.position += 1;
+            }
+        }
+
+        let token: String = self.chars[start..self.position.min(self.chars.len())]
+            .iter()
+            .collect();
+        (self.parse_value)(&token)
+            .map(NestedList::Value)
+            .map_err(|message| ParseError {
+                position: start,
+                message: format!("invalid value '{}': {}", token, message),
+            })
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.position += 1;
+        }
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        ParseError {
+            position: self.position,
+            message: message.to_owned(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// xorshift, good enough to generate test data without pulling in a crate
+    struct Random(u64);
+
+    impl Random {
+        fn next(&mut self, below: u64) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0 % below
+        }
+
+        fn list(&mut self, depth: u32) -> NestedList<i64> {
+            if depth == 0 || self.next(3) == 0 {
+                return NestedList::Value(self.next(2001) as i64 - 1000);
+            }
+            let length = self.next(5);
+            NestedList::List((0..length).map(|_| self.list(depth - 1)).collect())
+        }
+    }
+
+    fn parse(string: &str) -> NestedList<i64> {
+        string.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_of_write_gives_the_same_list() {
+        let mut random = Random(0x9e37_79b9_7f4a_7c15);
+
+        for _ in 0..1000 {
+            let list = random.list(5);
+            let written = list.to_string();
+            assert_eq!(parse(&written), list, "{}", written);
+
+            let spaced = written.replace(',', " , ").replace('[', "[ ");
+            assert_eq!(parse(&format!("  {}\n", spaced)), list, "{}", spaced);
+
+            assert_eq!(NestedList::from_json(&list.to_json()), Ok(list));
+        }
+    }
+
+    #[test]
+    fn reports_where_parsing_fails() {
+        let error = "[1,[2,x]]".parse::<NestedList<i64>>().unwrap_err();
+        assert_eq!(error.position, 6);
+
+        assert_eq!("[1,2".parse::<NestedList<i64>>().unwrap_err().position, 4);
+        assert_eq!("[1,]".parse::<NestedList<i64>>().unwrap_err().position, 3);
+        assert_eq!("[1] 2".parse::<NestedList<i64>>().unwrap_err().position, 4);
+    }
+
+    #[test]
+    fn json_strings_roundtrip() {
+        let list = NestedList::from_json(r#"["a", ["b \"quoted\"", "c,]"]]"#).unwrap();
+        assert_eq!(
+            list,
+            NestedList::List(vec![
+                NestedList::Value("a".to_owned()),
+                NestedList::List(vec![
+                    NestedList::Value("b \"quoted\"".to_owned()),
+                    NestedList::Value("c,]".to_owned()),
+                ]),
+            ])
+        );
+        assert_eq!(NestedList::from_json(&list.to_json()), Ok(list));
+
+        let escaped = NestedList::Value("tab\t cr\r\n \u{8}\u{c}\u{1} \\/ é 🎄".to_owned());
+        let json = escaped.to_json();
+        assert_eq!(json, r#""tab\t cr\r\n \b\f\u0001 \\/ é 🎄""#);
+        assert_eq!(NestedList::from_json(&json), Ok(escaped.clone()));
+        assert_eq!(
+            NestedList::from_json(r#""tab\u0009 cr\r\n \b\f\u0001 \\\/ \u00e9 \ud83c\udf84""#),
+            Ok(escaped)
+        );
+
+        assert!(NestedList::<String>::from_json(r#""\ud83c""#).is_err());
+        assert!(NestedList::<String>::from_json(r#""\u12""#).is_err());
+        assert!(NestedList::<String>::from_json("\"\t\"").is_err());
+    }
+
+    #[test]
+    fn json_numbers_are_finite() {
+        assert_eq!(NestedList::from_json("[1.5,-2]"), Ok(parse_f64("[1.5,-2]")));
+        assert!(NestedList::<f64>::from_json("[NaN]").is_err());
+        assert!(NestedList::<f64>::from_json("[inf]").is_err());
+        assert_eq!(NestedList::Value(f64::INFINITY).to_json(), "null");
+    }
+
+    fn parse_f64(string: &str) -> NestedList<f64> {
+        string.parse().unwrap()
+    }
+
+    #[test]
+    fn comparison_semantics() {
+        let value = parse("2");
+        let list = parse("[2]");
+
+        assert_eq!(value.compare(&list, Comparison::WrapValue), Ordering::Equal);
+        assert_eq!(
+            value.compare(&list, Comparison::ValuesFirst),
+            Ordering::Less
+        );
+        assert_eq!(
+            value.compare(&list, Comparison::ListsFirst),
+            Ordering::Greater
+        );
+        assert_eq!(
+            parse("[[1],[2,3,4]]").compare(&parse("[[1],4]"), Comparison::WrapValue),
+            Ordering::Less
+        );
+        assert_eq!(
+            parse("[[]]").compare(&parse("[-1]"), Comparison::WrapValue),
+            Ordering::Less
+        );
+    }
+}
@@ -0,0 +1,371 @@
+use std::{
+    fmt::Display,
+    iter::Sum,
+    ops::{Add, Neg, Sub},
+    str::FromStr,
+};
+
+/// A number written in a balanced base, where the digits go from -(B / 2) to B / 2
+/// e.g. balanced base 5 (SNAFU) writes 8 as `2=` (2 * 5 - 2) and -1 as `-`
+/// The arithmetic works on the digits directly, so numbers are not limited to a machine word
+#[derive(Clone, Debug)]
+pub struct BalancedBase<const B: u32> {
+    /// Least significant digit first, without leading zeros
+    digits: Vec<i8>,
+    /// The symbols of the digits from the lowest to the highest value,
+    /// None until a number is parsed or given one, then the default alphabet is used
+    alphabet: Option<&'static str>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseBalancedError {
+    Empty,
+    InvalidDigit {
+        position: usize,
+        character: char,
+    },
+    /// The alphabet does not have exactly B different symbols
+    InvalidAlphabet,
+}
+
+impl Display for ParseBalancedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseBalancedError::Empty => write!(f, "empty number"),
+            ParseBalancedError::InvalidDigit {
+                position,
+                character,
+            } => write!(f, "invalid digit '{}' at position {}", character, position),
+            ParseBalancedError::InvalidAlphabet => write!(f, "invalid alphabet"),
+        }
+    }
+}
+
+impl std::error::Error for ParseBalancedError {}
+
+impl<const B: u32> BalancedBase<B> {
+    const HALF: i8 = {
+        assert!(
+            B % 2 == 1 && B >= 3 && B <= 63,
+            "A balanced base has to be odd"
+        );
+        (B / 2) as i8
+    };
+
+    /// `-0+` for balanced ternary and `=-012` for SNAFU, other bases need their own alphabet
+    pub fn default_alphabet() -> Option<&'static str> {
+        match B {
+            3 => Some("-0+"),
+            5 => Some("=-012"),
+            _ => None,
+        }
+    }
+
+    /// Zero without an alphabet, it takes the alphabet of the first number it is added to
+    pub fn zero() -> Self {
+        Self {
+            digits: vec![],
+            alphabet: None,
+        }
+    }
+
+    /// The symbols this number is written with
+    pub fn alphabet(&self) -> Option<&'static str> {
+        self.alphabet.or_else(Self::default_alphabet)
+    }
+
+    /// Parses with the given symbols, ordered from the lowest to the highest digit value
+    pub fn parse_with_alphabet(
+        string: &str,
+        alphabet: &'static str,
+    ) -> Result<Self, ParseBalancedError> {
+        Self::check_alphabet(alphabet)?;
+
+        if string.is_empty() {
+            return Err(ParseBalancedError::Empty);
+        }
+
+        let digits = string
+            .chars()
+            .enumerate()
+            .map(|(position, character)| {
+                alphabet
+                    .chars()
+                    .position(|c| c == character)
+                    .map(|value| value as i8 - Self::HALF)
+                    .ok_or(ParseBalancedError::InvalidDigit {
+                        position,
+                        character,
+                    })
+            })
+            .collect::<Result<Vec<i8>, _>>()?;
+
+        Ok(Self::from_digits(
+            digits.into_iter().rev().collect(),
+            Some(alphabet),
+        ))
+    }
+
+    /// The same number, written with other symbols
+    pub fn with_alphabet(self, alphabet: &'static str) -> Result<Self, ParseBalancedError> {
+        Self::check_alphabet(alphabet)?;
+        Ok(Self {
+            alphabet: Some(alphabet),
+            ..self
+        })
+    }
+
+    fn check_alphabet(alphabet: &str) -> Result<(), ParseBalancedError> {
+        let symbols: Vec<char> = alphabet.chars().collect();
+        let unique = symbols
+            .iter()
+            .enumerate()
+            .all(|(i, c)| !symbols[..i].contains(c));
+
+        if symbols.len() != B as usize || !unique {
+            return Err(ParseBalancedError::InvalidAlphabet);
+        }
+        Ok(())
+    }
+
+    fn from_digits(mut digits: Vec<i8>, alphabet: Option<&'static str>) -> Self {
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        Self { digits, alphabet }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    /// The sign of a balanced number is the sign of its most significant digit
+    pub fn is_negative(&self) -> bool {
+        self.digits.last().is_some_and(|d| *d < 0)
+    }
+
+    /// The value, if it fits
+    pub fn to_i64(&self) -> Option<i64> {
+        // the partial values can leave the i64 range even when the result does not
+        self.digits
+            .iter()
+            .rev()
+            .try_fold(0i128, |value, digit| {
+                value.checked_mul(B as i128)?.checked_add(*digit as i128)
+            })?
+            .try_into()
+            .ok()
+    }
+}
+
+impl<const B: u32> Default for BalancedBase<B> {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl<const B: u32> From<i64> for BalancedBase<B> {
+    fn from(value: i64) -> Self {
+        let base = B as i128;
+        let mut value = value as i128;
+        let mut digits = vec![];
+
+        while value != 0 {
+            let mut digit = value.rem_euclid(base);
+            if digit > Self::HALF as i128 {
+                digit -= base;
+            }
+            digits.push(digit as i8);
+            value = (value - digit) / base;
+        }
+
+        Self::from_digits(digits, None)
+    }
+}
+
+impl<const B: u32> FromStr for BalancedBase<B> {
+    type Err = ParseBalancedError;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let alphabet = Self::default_alphabet().ok_or(ParseBalancedError::InvalidAlphabet)?;
+        Self::parse_with_alphabet(string, alphabet)
+    }
+}
+
+/// Without an alphabet, in a base with no default one, the digit values are written from the
+/// most significant one in brackets, e.g. `[3,-2]` for 19 in balanced base 7
+impl<const B: u32> Display for BalancedBase<B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let zero = [0];
+        let digits = if self.is_zero() {
+            &zero[..]
+        } else {
+            &self.digits[..]
+        };
+
+        let Some(alphabet) = self.alphabet() else {
+            let values: Vec<String> = digits.iter().rev().map(|d| d.to_string()).collect();
+            return write!(f, "[{}]", values.join(","));
+        };
+
+        let symbols: Vec<char> = alphabet.chars().collect();
+        for digit in digits.iter().rev() {
+            write!(f, "{}", symbols[(digit + Self::HALF) as usize])?;
+        }
+        Ok(())
+    }
+}
+
+/// Numbers are equal when their digits are, whatever symbols they are written with
+impl<const B: u32> PartialEq for BalancedBase<B> {
+    fn eq(&self, other: &Self) -> bool {
+        self.digits == other.digits
+    }
+}
+
+impl<const B: u32> Eq for BalancedBase<B> {}
+
+impl<const B: u32> Neg for BalancedBase<B> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            digits: self.digits.iter().map(|d| -d).collect(),
+            alphabet: self.alphabet,
+        }
+    }
+}
+
+impl<const B: u32> Add for BalancedBase<B> {
+    type Output = Self;
+
+    /// Adds digit by digit, carrying whenever a digit leaves the balanced range
+    /// The sum is written with the alphabet of the left number, or else the right one
+    fn add(self, rhs: Self) -> Self::Output {
+        let length = self.digits.len().max(rhs.digits.len());
+        let mut digits = Vec::with_capacity(length + 1);
+        let mut carry = 0;
+
+        for i in 0..length {
+            let mut digit =
+                self.digits.get(i).unwrap_or(&0) + rhs.digits.get(i).unwrap_or(&0) + carry;
+
+            carry = 0;
+            if digit > Self::HALF {
+                digit -= B as i8;
+                carry = 1;
+            } else if digit < -Self::HALF {
+                digit += B as i8;
+                carry = -1;
+            }
+
+            digits.push(digit);
+        }
+        digits.push(carry);
+
+        Self::from_digits(digits, self.alphabet.or(rhs.alphabet))
+    }
+}
+
+impl<const B: u32> Sub for BalancedBase<B> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+/// The sum is written with the alphabet of the first number
+impl<const B: u32> Sum for BalancedBase<B> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.reduce(Add::add).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Snafu = BalancedBase<5>;
+
+    #[test]
+    fn writes_and_parses_every_number() {
+        for value in -2000..=2000 {
+            let number = Snafu::from(value);
+            let written = number.to_string();
+            assert_eq!(written.parse::<Snafu>(), Ok(number.clone()), "{}", written);
+            assert_eq!(number.to_i64(), Some(value));
+            assert_eq!(number.is_negative(), value < 0);
+        }
+
+        assert_eq!(Snafu::from(0).to_string(), "0");
+        assert_eq!(Snafu::from(-3).to_string(), "-2");
+        assert_eq!(Snafu::from(2022).to_string(), "1=11-2");
+        assert_eq!("000".parse::<Snafu>(), Ok(Snafu::zero()));
+        assert_eq!(Snafu::from(i64::MIN).to_i64(), Some(i64::MIN));
+    }
+
+    #[test]
+    fn arithmetic_on_digits() {
+        let a: Snafu = "1=-0-2".parse().unwrap();
+        let b: Snafu = "12111".parse().unwrap();
+        assert_eq!((a.clone() + b.clone()).to_i64(), Some(1747 + 906));
+        assert_eq!((b.clone() - a.clone()).to_i64(), Some(906 - 1747));
+        assert_eq!((a.clone() - a).to_string(), "0");
+
+        let big = Snafu::from(i64::MAX) + Snafu::from(i64::MAX);
+        assert_eq!(big.to_i64(), None);
+        assert_eq!((big - Snafu::from(i64::MAX)).to_i64(), Some(i64::MAX));
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        assert_eq!(
+            "12a1".parse::<Snafu>(),
+            Err(ParseBalancedError::InvalidDigit {
+                position: 2,
+                character: 'a'
+            })
+        );
+        assert_eq!("".parse::<Snafu>(), Err(ParseBalancedError::Empty));
+        assert_eq!(
+            Snafu::parse_with_alphabet("1", "=-01"),
+            Err(ParseBalancedError::InvalidAlphabet)
+        );
+    }
+
+    #[test]
+    fn custom_alphabet() {
+        let ternary = BalancedBase::<3>::parse_with_alphabet("1T0", "T01").unwrap();
+        assert_eq!(ternary.to_i64(), Some(6));
+        assert_eq!(
+            ternary.clone().with_alphabet("-0+").unwrap().to_string(),
+            "+-0"
+        );
+        assert_eq!(ternary, BalancedBase::<3>::from(6));
+    }
+
+    #[test]
+    fn sums_keep_the_alphabet() {
+        type Septenary = BalancedBase<7>;
+        let alphabet = "cba0ABC";
+
+        let sum: Septenary = ["A0", "Cc", "b"]
+            .iter()
+            .map(|n| Septenary::parse_with_alphabet(n, alphabet).unwrap())
+            .sum();
+        assert_eq!(sum.to_i64(), Some(7 + 18 - 2));
+        assert_eq!(sum.to_string(), "CB");
+        assert_eq!(sum.alphabet(), Some(alphabet));
+
+        let empty: Septenary = std::iter::empty().sum();
+        assert!(empty.is_zero());
+        assert_eq!(Septenary::from(23).with_alphabet(alphabet).unwrap(), sum);
+        assert_eq!(Septenary::from(19).to_string(), "[3,-2]");
+        assert_eq!(Septenary::zero().to_string(), "[0]");
+        assert_eq!(
+            "1".parse::<Septenary>(),
+            Err(ParseBalancedError::InvalidAlphabet)
+        );
+    }
+}
@@ -0,0 +1,13 @@
+//! The types and traits most days need, so a day can start with `use aoc_helper::prelude::*;`
+//! These names stay the same when the modules behind them are reorganised
+
+pub use crate::collections::{
+    DirectionalCollection, IndexedRing, IntervalSet, PriorityQueue, RingHandle, SparseGrid,
+};
+pub use crate::math;
+pub use crate::navigation;
+pub use crate::runner::{
+    ProcessAndAssert, ProcessAndWrite, ProcessOwnedAndAssert, ProcessOwnedAndWrite,
+    ProcessWithParamsAndAssert, ProcessWithParamsAndWrite, Runner,
+};
+pub use crate::vectors::{UVec2D, Vec2D};
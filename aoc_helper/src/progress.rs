@@ -0,0 +1,239 @@
+use std::{
+    cell::RefCell,
+    fmt::Display,
+    io::{IsTerminal, Write},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+const REPORT_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Reports how far a long running part is on stderr, instead of mixing it with the answers
+/// A silent handle ignores everything, this is what parts get when they are not run by the runner
+/// (e.g. in tests), or when stderr is not a terminal
+#[derive(Clone, Default)]
+pub struct Progress {
+    inner: Option<Arc<Inner>>,
+}
+
+struct Inner {
+    label: String,
+    start: Instant,
+    total: AtomicU64,
+    done: AtomicU64,
+    best: Mutex<Option<String>>,
+    last_report: Mutex<Option<Instant>>,
+    finished: AtomicBool,
+    output: Mutex<Box<dyn Write + Send>>,
+}
+
+thread_local! {
+    static CURRENT: RefCell<Progress> = RefCell::new(Progress::silent());
+}
+
+/// Returns the handle the runner installed for the part running on this thread
+pub fn current() -> Progress {
+    CURRENT.with(|c| c.borrow().clone())
+}
+
+/// Makes `progress` the handle returned by `current` on this thread
+pub fn install(progress: Progress) {
+    CURRENT.with(|c| *c.borrow_mut() = progress);
+}
+
+impl Progress {
+    pub fn silent() -> Self {
+        Self { inner: None }
+    }
+
+    /// Reports on stderr, but only if somebody is watching it
+    pub fn stderr(label: &str) -> Self {
+        if !std::io::stderr().is_terminal() {
+            return Self::silent();
+        }
+
+        Self::to_output(label, Box::new(std::io::stderr()))
+    }
+
+    fn to_output(label: &str, output: Box<dyn Write + Send>) -> Self {
+        Self {
+            inner: Some(Arc::new(Inner {
+                label: label.to_owned(),
+                start: Instant::now(),
+                total: AtomicU64::new(0),
+                done: AtomicU64::new(0),
+                best: Mutex::new(None),
+                last_report: Mutex::new(None),
+                finished: AtomicBool::new(false),
+                output: Mutex::new(output),
+            })),
+        }
+    }
+
+    pub fn is_silent(&self) -> bool {
+        self.inner.is_none()
+    }
+
+    /// The amount of steps `update` counts towards, needed for the percentage and ETA
+    pub fn set_total(&self, total: u64) {
+        if let Some(inner) = &self.inner {
+            inner.total.store(total, Ordering::Relaxed);
+        }
+    }
+
+    /// Sets the amount of finished steps
+    pub fn update(&self, done: u64) {
+        if let Some(inner) = &self.inner {
+            inner.done.store(done, Ordering::Relaxed);
+            inner.report();
+        }
+    }
+
+    /// Records the best value found so far, e.g. the current maximum of a search
+    pub fn best(&self, value: impl Display) {
+        if let Some(inner) = &self.inner {
+            *inner.best.lock().unwrap() = Some(value.to_string());
+            inner.report();
+        }
+    }
+
+    /// Removes the progress line so the answer can be written, later reports are ignored
+    pub fn finish(&self) {
+        if let Some(inner) = &self.inner {
+            if !inner.finished.swap(true, Ordering::Relaxed)
+                && inner.last_report.lock().unwrap().is_some()
+            {
+                inner.write("");
+            }
+        }
+    }
+}
+
+impl Inner {
+    fn report(&self) {
+        // skip when another thread is reporting right now
+        let Ok(mut last_report) = self.last_report.try_lock() else {
+            return;
+        };
+
+        if self.finished.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let now = Instant::now();
+        if last_report.is_some_and(|last| now - last < REPORT_INTERVAL) {
+            return;
+        }
+        *last_report = Some(now);
+
+        let mut line = format!("{}:", self.label);
+
+        let total = self.total.load(Ordering::Relaxed);
+        let done = self.done.load(Ordering::Relaxed).min(total);
+
+        if total > 0 {
+            let fraction = done as f64 / total as f64;
+            line += &format!(" {:5.1}%", fraction * 100.0);
+
+            if fraction > 0.0 {
+                let elapsed = self.start.elapsed().as_secs_f64();
+                let remaining = Duration::from_secs_f64(elapsed / fraction - elapsed);
+                line += &format!(", ETA {:.0?}", remaining);
+            }
+        }
+
+        if let Some(best) = self.best.lock().unwrap().as_ref() {
+            line += &format!(", best {}", best);
+        }
+
+        self.write(&line);
+    }
+
+    /// Replaces the current line
+    fn write(&self, line: &str) {
+        let mut output = self.output.lock().unwrap();
+        _ = write!(output, "\r\x1B[2K{}", line);
+        _ = output.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Collects what a handle writes, so the tests can look at it
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Buffer {
+        fn text(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn silent_outside_the_runner() {
+        // a fresh thread, so no handle installed by another test is seen
+        let progress = std::thread::spawn(current).join().unwrap();
+        assert!(progress.is_silent());
+
+        progress.set_total(10);
+        progress.update(5);
+        progress.best(3);
+        progress.finish();
+    }
+
+    #[test]
+    fn reports_at_most_once_per_interval() {
+        let buffer = Buffer::default();
+        let progress = Progress::to_output("Part 1", Box::new(buffer.clone()));
+
+        progress.set_total(4);
+        progress.update(1);
+        progress.update(2);
+        progress.best(7);
+        // the first update is reported, the others come too soon after it
+        assert!(buffer.text().starts_with("\r\x1B[2KPart 1:  25.0%, ETA "));
+        assert_eq!(buffer.text().matches('\r').count(), 1);
+
+        progress.finish();
+        progress.update(3);
+        assert_eq!(
+            buffer.text().matches('\r').count(),
+            2,
+            "only the line and clearing it"
+        );
+        assert!(buffer.text().ends_with("\r\x1B[2K"));
+    }
+
+    #[test]
+    fn current_returns_the_installed_handle() {
+        let buffer = Buffer::default();
+        let progress = Progress::to_output("Part 2", Box::new(buffer.clone()));
+
+        let installed = std::thread::spawn(move || {
+            install(progress);
+            current().update(1);
+            current()
+        })
+        .join()
+        .unwrap();
+
+        assert!(!installed.is_silent());
+        assert_eq!(buffer.text(), "\r\x1B[2KPart 2:");
+    }
+}
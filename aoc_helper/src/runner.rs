@@ -1,7 +1,26 @@
-use std::fmt::{Debug, Display};
+use std::{
+    cell::RefCell,
+    fmt::{Debug, Display},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
 
-pub struct Runner<Input> {
-    input: Input,
+use crate::progress::{self, Progress};
+
+/// Environment variable with the default time budget per part in seconds, used by all_days
+pub const PART_TIMEOUT_VARIABLE: &str = "AOC_PART_TIMEOUT";
+
+/// Holds the parsed input, and optionally some parameters that differ per input file
+/// (e.g. the row to check, which is different for test.txt and input.txt)
+pub struct Runner<Input, Params = ()> {
+    input: Arc<Input>,
+    params: Arc<Params>,
+    timeout: Option<Duration>,
 }
 
 impl<I> Runner<I> {
@@ -25,11 +44,53 @@ impl<I> Runner<I> {
     {
         let input: String = std::fs::read_to_string(path).expect("File could not be read");
         Runner {
-            input: input_parser(input),
+            input: Arc::new(input_parser(input)),
+            params: Arc::new(()),
+            timeout: timeout_from_environment(),
         }
     }
 }
 
+impl<I, P> Runner<I, P> {
+    pub fn with_params<Q>(self, params: Q) -> Runner<I, Q> {
+        Runner {
+            input: self.input,
+            params: Arc::new(params),
+            timeout: self.timeout,
+        }
+    }
+
+    /// Parts that take longer than the timeout are reported as TIMEOUT, the runner then continues
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+fn timeout_from_environment() -> Option<Duration> {
+    parse_timeout(&std::env::var(PART_TIMEOUT_VARIABLE).ok()?)
+}
+
+/// Seconds, fractions are allowed, anything that is not a valid duration means no timeout
+fn parse_timeout(seconds: &str) -> Option<Duration> {
+    Duration::try_from_secs_f64(seconds.trim().parse().ok()?).ok()
+}
+
+thread_local! {
+    static CANCELLED: RefCell<Option<Arc<AtomicBool>>> = const { RefCell::new(None) };
+}
+
+/// Returns true when the part running on this thread ran out of time
+/// Long running parts can check this to stop early, the result is discarded anyway
+/// Only the thread the runner started for the part sees this, not threads the part spawns itself
+pub fn is_cancelled() -> bool {
+    CANCELLED.with(|c| {
+        c.borrow()
+            .as_ref()
+            .is_some_and(|cancelled| cancelled.load(Ordering::Relaxed))
+    })
+}
+
 pub trait ProcessAndWrite<'a, I> {
     fn process_and_write<F, O>(&'a self, string: &str, processor: F)
     where
@@ -39,67 +100,171 @@ pub trait ProcessAndWrite<'a, I> {
     fn process_and_write_part_1<F, O>(&'a self, processor: F)
     where
         F: Fn(I) -> O,
-        O: Display;
+        O: Display,
+    {
+        self.process_and_write("Part 1", processor)
+    }
 
     fn process_and_write_part_2<F, O>(&'a self, processor: F)
     where
         F: Fn(I) -> O,
-        O: Display;
+        O: Display,
+    {
+        self.process_and_write("Part 2", processor)
+    }
 }
 
-impl<'a, I> ProcessAndWrite<'a, &'a [I]> for Runner<Vec<I>> {
+impl<'a, I, P> ProcessAndWrite<'a, &'a [I]> for Runner<Vec<I>, P> {
     fn process_and_write<F, O>(&'a self, string: &str, processor: F)
     where
         F: Fn(&'a [I]) -> O,
         O: Display,
     {
-        let now = start_timer();
-        let result = processor(&self.input);
-        stop_timer_and_write(now, string, result);
+        inline_and_write(string, || processor(&self.input));
     }
+}
 
-    fn process_and_write_part_1<F, O>(&'a self, processor: F)
+impl<'a, I, P> ProcessAndWrite<'a, &'a I> for Runner<I, P> {
+    fn process_and_write<F, O>(&'a self, string: &str, processor: F)
     where
-        F: Fn(&'a [I]) -> O,
+        F: Fn(&'a I) -> O,
         O: Display,
     {
-        self.process_and_write("Part 1", processor)
+        inline_and_write(string, || processor(&self.input));
     }
+}
 
-    fn process_and_write_part_2<F, O>(&'a self, processor: F)
+pub trait ProcessWithParamsAndWrite<'a, I, P: 'a> {
+    fn process_with_params_and_write<F, O>(&'a self, string: &str, processor: F)
     where
-        F: Fn(&'a [I]) -> O,
+        F: Fn(I, &'a P) -> O,
+        O: Display;
+
+    fn process_with_params_and_write_part_1<F, O>(&'a self, processor: F)
+    where
+        F: Fn(I, &'a P) -> O,
         O: Display,
     {
-        self.process_and_write("Part 2", processor)
+        self.process_with_params_and_write("Part 1", processor)
+    }
+
+    fn process_with_params_and_write_part_2<F, O>(&'a self, processor: F)
+    where
+        F: Fn(I, &'a P) -> O,
+        O: Display,
+    {
+        self.process_with_params_and_write("Part 2", processor)
     }
 }
 
-impl<'a, I> ProcessAndWrite<'a, &'a I> for Runner<I> {
-    fn process_and_write<F, O>(&'a self, string: &str, processor: F)
+impl<'a, I, P: 'a> ProcessWithParamsAndWrite<'a, &'a [I], P> for Runner<Vec<I>, P> {
+    fn process_with_params_and_write<F, O>(&'a self, string: &str, processor: F)
     where
-        F: Fn(&'a I) -> O,
+        F: Fn(&'a [I], &'a P) -> O,
         O: Display,
     {
-        let now = start_timer();
-        let result = processor(&self.input);
-        stop_timer_and_write(now, string, result);
+        inline_and_write(string, || processor(&self.input, &self.params));
     }
+}
 
-    fn process_and_write_part_1<F, O>(&'a self, processor: F)
+impl<'a, I, P: 'a> ProcessWithParamsAndWrite<'a, &'a I, P> for Runner<I, P> {
+    fn process_with_params_and_write<F, O>(&'a self, string: &str, processor: F)
     where
-        F: Fn(&'a I) -> O,
+        F: Fn(&'a I, &'a P) -> O,
         O: Display,
     {
-        self.process_and_write("Part 1", processor)
+        inline_and_write(string, || processor(&self.input, &self.params));
     }
+}
 
-    fn process_and_write_part_2<F, O>(&'a self, processor: F)
+/// For parts that need to own or mutate their input, every call receives a fresh clone
+/// so part 1 and part 2 never see each other's changes
+pub trait ProcessOwnedAndWrite<I> {
+    fn process_owned_and_write<F, O>(&self, string: &str, processor: F)
     where
-        F: Fn(&'a I) -> O,
+        F: Fn(I) -> O,
+        O: Display;
+
+    fn process_owned_and_write_part_1<F, O>(&self, processor: F)
+    where
+        F: Fn(I) -> O,
         O: Display,
     {
-        self.process_and_write("Part 2", processor)
+        self.process_owned_and_write("Part 1", processor)
+    }
+
+    fn process_owned_and_write_part_2<F, O>(&self, processor: F)
+    where
+        F: Fn(I) -> O,
+        O: Display,
+    {
+        self.process_owned_and_write("Part 2", processor)
+    }
+}
+
+impl<I: Clone, P> ProcessOwnedAndWrite<I> for Runner<I, P> {
+    fn process_owned_and_write<F, O>(&self, string: &str, processor: F)
+    where
+        F: Fn(I) -> O,
+        O: Display,
+    {
+        let input = I::clone(&self.input);
+        inline_and_write(string, || processor(input));
+    }
+}
+
+/// For long running parts that should stop after the timeout of `with_timeout` or
+/// `AOC_PART_TIMEOUT`, they are then reported as TIMEOUT
+/// With a timeout the part runs on its own thread, that is why it and its answer have to be
+/// `Send + 'static`, the other traits run parts on the current thread and ignore the timeout
+pub trait ProcessWithTimeoutAndWrite<I: ?Sized> {
+    fn process_with_timeout_and_write<F, O>(&self, string: &str, processor: F)
+    where
+        F: Fn(&I) -> O + Send + 'static,
+        O: Display + Send + 'static;
+
+    fn process_with_timeout_and_write_part_1<F, O>(&self, processor: F)
+    where
+        F: Fn(&I) -> O + Send + 'static,
+        O: Display + Send + 'static,
+    {
+        self.process_with_timeout_and_write("Part 1", processor)
+    }
+
+    fn process_with_timeout_and_write_part_2<F, O>(&self, processor: F)
+    where
+        F: Fn(&I) -> O + Send + 'static,
+        O: Display + Send + 'static,
+    {
+        self.process_with_timeout_and_write("Part 2", processor)
+    }
+}
+
+impl<I, P> ProcessWithTimeoutAndWrite<[I]> for Runner<Vec<I>, P>
+where
+    I: Send + Sync + 'static,
+{
+    fn process_with_timeout_and_write<F, O>(&self, string: &str, processor: F)
+    where
+        F: Fn(&[I]) -> O + Send + 'static,
+        O: Display + Send + 'static,
+    {
+        let input = Arc::clone(&self.input);
+        timed_and_write(string, self.timeout, move || processor(&input));
+    }
+}
+
+impl<I, P> ProcessWithTimeoutAndWrite<I> for Runner<I, P>
+where
+    I: Send + Sync + 'static,
+{
+    fn process_with_timeout_and_write<F, O>(&self, string: &str, processor: F)
+    where
+        F: Fn(&I) -> O + Send + 'static,
+        O: Display + Send + 'static,
+    {
+        let input = Arc::clone(&self.input);
+        timed_and_write(string, self.timeout, move || processor(&input));
     }
 }
 
@@ -109,6 +274,88 @@ fn start_timer() -> Instant {
     Instant::now()
 }
 
+fn inline_and_write<J, O>(string: &str, job: J)
+where
+    J: FnOnce() -> O,
+    O: Display,
+{
+    let now = start_timer();
+    let result = run_inline(Progress::stderr(string), Arc::default(), job);
+    stop_timer_and_write(now, string, result);
+}
+
+fn timed_and_write<J, O>(string: &str, timeout: Option<Duration>, job: J)
+where
+    J: FnOnce() -> O + Send + 'static,
+    O: Display + Send + 'static,
+{
+    let now = start_timer();
+    match run_with_timeout(Progress::stderr(string), timeout, job) {
+        Some(result) => stop_timer_and_write(now, string, result),
+        None => stop_timer_and_write(now, string, "TIMEOUT"),
+    }
+}
+
+/// Runs the part on the current thread, with `progress::current()` and `is_cancelled`
+/// set up for it like on a worker, and puts back what was there before
+fn run_inline<J, O>(progress: Progress, cancelled: Arc<AtomicBool>, job: J) -> O
+where
+    J: FnOnce() -> O,
+{
+    let previous_cancelled = CANCELLED.with(|c| c.replace(Some(cancelled)));
+    let previous_progress = progress::current();
+    progress::install(progress.clone());
+
+    let result = job();
+
+    progress.finish();
+    progress::install(previous_progress);
+    CANCELLED.with(|c| *c.borrow_mut() = previous_cancelled);
+    result
+}
+
+/// Without a timeout the part runs on the current thread
+/// With one it runs on a worker thread, so a part that exceeds the timeout can be left behind,
+/// and None is returned
+/// The worker is not killed, it is told to stop through `is_cancelled`, a part that never checks
+/// it keeps running until the process exits
+/// The part can report its progress through `progress::current()`
+fn run_with_timeout<J, O>(progress: Progress, timeout: Option<Duration>, job: J) -> Option<O>
+where
+    J: FnOnce() -> O + Send + 'static,
+    O: Send + 'static,
+{
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let Some(timeout) = timeout else {
+        return Some(run_inline(progress, cancelled, job));
+    };
+
+    let (sender, receiver) = mpsc::channel();
+
+    let worker = {
+        let cancelled = Arc::clone(&cancelled);
+        let progress = progress.clone();
+        thread::spawn(move || {
+            let result = run_inline(progress, cancelled, job);
+            // the receiver is gone when the part timed out, so the result can be ignored
+            _ = sender.send(result);
+        })
+    };
+
+    match receiver.recv_timeout(timeout) {
+        Ok(result) => Some(result),
+        Err(RecvTimeoutError::Timeout) => {
+            cancelled.store(true, Ordering::Relaxed);
+            progress.finish();
+            None
+        }
+        Err(RecvTimeoutError::Disconnected) => match worker.join() {
+            Err(panic) => std::panic::resume_unwind(panic),
+            Ok(()) => unreachable!("Worker finished without sending a result"),
+        },
+    }
+}
+
 fn stop_timer_and_write<O>(now: Instant, string: &str, result: O)
 where
     O: Display,
@@ -129,7 +376,7 @@ pub trait ProcessAndAssert<'a, I> {
         O: Debug;
 }
 
-impl<'a, I> ProcessAndAssert<'a, &'a [I]> for Runner<Vec<I>> {
+impl<'a, I, P> ProcessAndAssert<'a, &'a [I]> for Runner<Vec<I>, P> {
     fn process_and_assert<F, O>(&'a self, processor: F, expected: O)
     where
         F: Fn(&'a [I]) -> O,
@@ -140,7 +387,7 @@ impl<'a, I> ProcessAndAssert<'a, &'a [I]> for Runner<Vec<I>> {
     }
 }
 
-impl<'a, I> ProcessAndAssert<'a, &'a I> for Runner<I> {
+impl<'a, I, P> ProcessAndAssert<'a, &'a I> for Runner<I, P> {
     fn process_and_assert<F, O>(&'a self, processor: F, expected: O)
     where
         F: Fn(&'a I) -> O,
@@ -150,3 +397,107 @@ impl<'a, I> ProcessAndAssert<'a, &'a I> for Runner<I> {
         assert_eq!(processor(&self.input), expected)
     }
 }
+
+pub trait ProcessWithParamsAndAssert<'a, I, P: 'a> {
+    fn process_with_params_and_assert<F, O>(&'a self, processor: F, expected: O)
+    where
+        F: Fn(I, &'a P) -> O,
+        O: PartialEq,
+        O: Debug;
+}
+
+impl<'a, I, P: 'a> ProcessWithParamsAndAssert<'a, &'a [I], P> for Runner<Vec<I>, P> {
+    fn process_with_params_and_assert<F, O>(&'a self, processor: F, expected: O)
+    where
+        F: Fn(&'a [I], &'a P) -> O,
+        O: PartialEq,
+        O: Debug,
+    {
+        assert_eq!(processor(&self.input, &self.params), expected)
+    }
+}
+
+impl<'a, I, P: 'a> ProcessWithParamsAndAssert<'a, &'a I, P> for Runner<I, P> {
+    fn process_with_params_and_assert<F, O>(&'a self, processor: F, expected: O)
+    where
+        F: Fn(&'a I, &'a P) -> O,
+        O: PartialEq,
+        O: Debug,
+    {
+        assert_eq!(processor(&self.input, &self.params), expected)
+    }
+}
+
+pub trait ProcessOwnedAndAssert<I> {
+    fn process_owned_and_assert<F, O>(&self, processor: F, expected: O)
+    where
+        F: Fn(I) -> O,
+        O: PartialEq,
+        O: Debug;
+}
+
+impl<I: Clone, P> ProcessOwnedAndAssert<I> for Runner<I, P> {
+    fn process_owned_and_assert<F, O>(&self, processor: F, expected: O)
+    where
+        F: Fn(I) -> O,
+        O: PartialEq,
+        O: Debug,
+    {
+        assert_eq!(processor(I::clone(&self.input)), expected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn returns_parts_that_finish_in_time() {
+        let result = run_with_timeout(Progress::silent(), Some(Duration::from_secs(10)), || {
+            assert!(!is_cancelled());
+            42
+        });
+        assert_eq!(result, Some(42));
+
+        // without a timeout there is no need for another thread
+        let here = thread::current().id();
+        let there = run_with_timeout(Progress::silent(), None, || thread::current().id());
+        assert_eq!(there, Some(here));
+    }
+
+    #[test]
+    fn cancels_parts_that_time_out() {
+        let (sender, receiver) = mpsc::channel();
+
+        let result = run_with_timeout(
+            Progress::silent(),
+            Some(Duration::from_millis(20)),
+            move || {
+                while !is_cancelled() {
+                    thread::sleep(Duration::from_millis(1));
+                }
+                sender.send(()).unwrap();
+            },
+        );
+
+        assert_eq!(result, None);
+        // the worker saw the cancellation and stopped
+        assert!(receiver.recv_timeout(Duration::from_secs(10)).is_ok());
+    }
+
+    #[test]
+    fn reads_timeouts() {
+        assert_eq!(parse_timeout("1.5"), Some(Duration::from_millis(1500)));
+        assert_eq!(parse_timeout(" 2 "), Some(Duration::from_secs(2)));
+        assert_eq!(parse_timeout("-1"), None);
+        assert_eq!(parse_timeout("soon"), None);
+
+        let runner = Runner {
+            input: Arc::new(()),
+            params: Arc::new(()),
+            timeout: None,
+        }
+        .with_timeout(Duration::from_secs(3));
+        assert_eq!(runner.timeout, Some(Duration::from_secs(3)));
+    }
+}
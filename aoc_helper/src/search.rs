@@ -0,0 +1,151 @@
+use std::cell::Cell;
+
+use crate::vectors::Vec2D;
+
+/// A grid with obstacles that move, but repeat after `period` turns
+/// Whether a cell is blocked is remembered for every turn in the period, the first time it is
+/// asked, and positions reached at the same turn in the period are the same state
+pub struct TimeExpanded<F: Fn(Vec2D, usize) -> bool> {
+    bounds: Vec2D,
+    period: usize,
+    is_blocked: F,
+    /// Indexed by `turn * width * height + y * width + x` for turns in the period,
+    /// None until the cell is looked up
+    blocked: Vec<Cell<Option<bool>>>,
+}
+
+impl<F: Fn(Vec2D, usize) -> bool> TimeExpanded<F> {
+    /// `is_blocked` tells if a position in the grid is blocked at a turn, from 0 up to the period
+    pub fn new(bounds: Vec2D, period: usize, is_blocked: F) -> Self {
+        assert!(period > 0, "The period should be at least one turn");
+
+        Self {
+            bounds,
+            period,
+            is_blocked,
+            blocked: vec![Cell::new(None); period * (bounds.x * bounds.y) as usize],
+        }
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Positions outside the grid are never free
+    pub fn is_free(&self, position: Vec2D, turn: usize) -> bool {
+        if !position.is_in_bounds(self.bounds) {
+            return false;
+        }
+
+        let turn = turn % self.period;
+        let cell = &self.blocked[self.index(position, turn)];
+        let blocked = cell.get().unwrap_or_else(|| {
+            let blocked = (self.is_blocked)(position, turn);
+            cell.set(Some(blocked));
+            blocked
+        });
+        !blocked
+    }
+
+    /// The index of a position inside the grid at a turn in the period
+    fn index(&self, position: Vec2D, turn: usize) -> usize {
+        let cells = (self.bounds.x * self.bounds.y) as usize;
+        turn * cells + (position.y * self.bounds.x + position.x) as usize
+    }
+
+    /// The first turn at which `to` can be reached when leaving `from` at `start_turn`
+    /// Every turn one of `moves` is made, include a zero move to allow waiting in place
+    pub fn shortest_path(
+        &self,
+        from: Vec2D,
+        to: Vec2D,
+        start_turn: usize,
+        moves: &[Vec2D],
+    ) -> Option<usize> {
+        if from == to {
+            return Some(start_turn);
+        }
+
+        // indexed like `blocked`, a position at a turn in the period is only visited once
+        let mut seen = vec![false; self.blocked.len()];
+        let mut frontier = vec![from];
+        let mut next = vec![];
+        let mut turn = start_turn;
+
+        while !frontier.is_empty() {
+            turn += 1;
+
+            for position in frontier.drain(..) {
+                for step in moves {
+                    let position = position + *step;
+                    if !self.is_free(position, turn) {
+                        continue;
+                    }
+                    if position == to {
+                        return Some(turn);
+                    }
+
+                    let index = self.index(position, turn % self.period);
+                    if !seen[index] {
+                        seen[index] = true;
+                        next.push(position);
+                    }
+                }
+            }
+
+            std::mem::swap(&mut frontier, &mut next);
+        }
+
+        None
+    }
+
+    /// Visits the waypoints in order, each leg starts when the previous one arrived
+    /// Returns the turn of arrival at the last waypoint
+    pub fn trip(&self, waypoints: &[Vec2D], start_turn: usize, moves: &[Vec2D]) -> Option<usize> {
+        waypoints.windows(2).try_fold(start_turn, |turn, leg| {
+            self.shortest_path(leg[0], leg[1], turn, moves)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::navigation;
+
+    #[test]
+    fn waits_for_a_moving_obstacle() {
+        // a 3x1 corridor with an obstacle moving back and forth over the middle and the end
+        let grid = TimeExpanded::new(Vec2D::new(3, 1), 2, |position, turn| {
+            position.x == 1 + (turn as i32 % 2)
+        });
+        let moves = navigation::get_adjecent_directions_including_self();
+
+        assert_eq!(grid.period(), 2);
+        assert!(!grid.is_free(Vec2D::new(1, 0), 4));
+        assert!(grid.is_free(Vec2D::new(1, 0), 5));
+        assert_eq!(
+            grid.shortest_path(Vec2D::new(0, 0), Vec2D::new(2, 0), 0, &moves),
+            Some(2)
+        );
+        assert_eq!(
+            grid.trip(
+                &[Vec2D::new(0, 0), Vec2D::new(2, 0), Vec2D::new(0, 0)],
+                0,
+                &moves
+            ),
+            Some(4)
+        );
+    }
+
+    #[test]
+    fn unreachable_targets_end_the_search() {
+        let grid = TimeExpanded::new(Vec2D::new(3, 1), 1, |position, _| position.x == 1);
+        let moves = navigation::get_adjecent_directions_including_self();
+
+        assert_eq!(
+            grid.shortest_path(Vec2D::new(0, 0), Vec2D::new(2, 0), 0, &moves),
+            None
+        );
+    }
+}
@@ -3,7 +3,7 @@ use std::{fmt::Display, ops::{Mul, Sub, Add, Div}};
 use crate::{vectors::UVec2D, math};
 
 /// Can be used when negative values are possible
-#[derive(Clone, Copy, Eq, PartialEq, Hash)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 pub struct Vec2D{
     pub x: i32,
     pub y: i32
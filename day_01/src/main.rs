@@ -1,4 +1,5 @@
 use aoc_helper::runner::{Runner, ProcessAndWrite};
+#[allow(clippy::module_inception)] // test.rs wraps its tests in a cfg(test) module
 mod test;
 
 fn main() {
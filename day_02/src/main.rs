@@ -1,3 +1,4 @@
+#[allow(clippy::module_inception)] // test.rs wraps its tests in a cfg(test) module
 mod test;
 use aoc_helper::{math, runner::{Runner, ProcessAndWrite}};
 
@@ -1,4 +1,5 @@
 use aoc_helper::{runner::{Runner, ProcessAndWrite}};
+#[allow(clippy::module_inception)] // test.rs wraps its tests in a cfg(test) module
 mod test;
 
 fn main() {
@@ -43,9 +44,9 @@ fn find_common_char(strings: &[String]) -> char {
 fn get_value(char: char) -> i32 {
     let value = char as i32 - 'a' as i32;
     if value < 0 {
-        return value + 58 + 1; // 58 is the diff between 'a' and 'A'
+        value + 58 + 1 // 58 is the diff between 'a' and 'A'
     }
     else {
-        return value + 1
+        value + 1
     }
 }
@@ -1,4 +1,5 @@
 use aoc_helper::{runner::{Runner, ProcessAndWrite}};
+#[allow(clippy::module_inception)] // test.rs wraps its tests in a cfg(test) module
 mod test;
 
 fn main() {
@@ -14,6 +15,7 @@ fn parse(input: String) -> Vec<String> {
 fn part_1(input: &[String]) -> usize {
     input
         .iter()
+        .map(String::as_str)
         .map(split_string_twice)
         .map(parse_nested_tuple)
         .filter(has_full_overlap)
@@ -23,6 +25,7 @@ fn part_1(input: &[String]) -> usize {
 fn part_2(input: &[String]) -> usize {
     input
         .iter()
+        .map(String::as_str)
         .map(split_string_twice)
         .map(parse_nested_tuple)
         .filter(has_partial_overlap)
@@ -47,7 +50,7 @@ fn parse_nested_tuple(tuple: ((&str, &str), (&str, &str))) -> (u32, u32, u32, u3
     (ll, lu, rl, ru)
 }
 
-fn split_string_twice(string: &String) -> ((&str, &str), (&str, &str)) {
+fn split_string_twice(string: &str) -> ((&str, &str), (&str, &str)) {
     let (left, right) = split_string(string, ',');
     (split_string(left, '-'), split_string(right, '-'))
 }
@@ -57,4 +60,4 @@ fn split_string(line: &str, char: char) -> (&str, &str) {
         return (left, right);
     }
     panic!("Could not split string");
-}
\ No newline at end of file
+}
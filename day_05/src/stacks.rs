@@ -0,0 +1,207 @@
+use std::fmt::Display;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum StackError {
+    /// The drawing could not be read, with the reason
+    InvalidDrawing(String),
+    /// A step could not be read, with the line
+    InvalidStep(String),
+    /// A step refers to a stack that does not exist, stacks are numbered from 1
+    UnknownStack(usize),
+    /// A step takes more crates from a stack than there are on it
+    NotEnoughCrates {
+        stack: usize,
+        requested: usize,
+        available: usize,
+    },
+}
+
+impl Display for StackError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StackError::InvalidDrawing(reason) => write!(f, "invalid drawing: {}", reason),
+            StackError::InvalidStep(line) => write!(f, "invalid step: '{}'", line),
+            StackError::UnknownStack(stack) => write!(f, "stack {} does not exist", stack),
+            StackError::NotEnoughCrates {
+                stack,
+                requested,
+                available,
+            } => write!(
+                f,
+                "cannot take {} crates from stack {}, it has {}",
+                requested, stack, available
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StackError {}
+
+/// Moves `count` crates, stacks are numbered from 1 like in the puzzle
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub count: usize,
+    pub from: usize,
+    pub to: usize,
+}
+
+impl Step {
+    //move 1 from 2 to 1
+    pub fn parse(line: &str) -> Result<Self, StackError> {
+        let error = || StackError::InvalidStep(line.to_owned());
+
+        match line.split(' ').collect::<Vec<&str>>()[..] {
+            ["move", count, "from", from, "to", to] => Ok(Self {
+                count: count.parse().map_err(|_| error())?,
+                from: from.parse().map_err(|_| error())?,
+                to: to.parse().map_err(|_| error())?,
+            }),
+            _ => Err(error()),
+        }
+    }
+}
+
+/// The stacks of crates, each from bottom to top
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Stacks {
+    stacks: Vec<Vec<char>>,
+}
+
+impl Stacks {
+    /// Reads the drawing, the last line numbers the stacks
+    /// Lines may be cut off after their last crate
+    pub fn parse(drawing: &[&str]) -> Result<Self, StackError> {
+        let error = |reason: &str| StackError::InvalidDrawing(reason.to_owned());
+
+        let (numbers, crates) = drawing.split_last().ok_or_else(|| error("empty drawing"))?;
+        let count = numbers.split_whitespace().count();
+        let mut stacks = vec![vec![]; count];
+
+        for (row, line) in crates.iter().rev().enumerate() {
+            let line: Vec<char> = line.chars().collect();
+
+            for (i, chunk) in line.chunks(4).enumerate() {
+                match chunk {
+                    // going up from the bottom, every lower row of the stack has a crate already
+                    ['[', _, ']', ..] if i < count && stacks[i].len() < row => {
+                        return Err(error("crate without a crate below it"))
+                    }
+                    ['[', name, ']', ..] if i < count => stacks[i].push(*name),
+                    ['[', ..] => return Err(error("crate outside the numbered stacks")),
+                    chunk if chunk.iter().all(|c| *c == ' ') => {}
+                    _ => return Err(error("expected a crate like [A]")),
+                }
+            }
+        }
+
+        Ok(Self { stacks })
+    }
+
+    /// Moves the crates of every step with the crane
+    /// A step is checked before it is done, so after an error the stacks show the previous step
+    pub fn apply(&mut self, crane: &impl Crane, steps: &[Step]) -> Result<(), StackError> {
+        steps.iter().try_for_each(|step| {
+            self.check(step)?;
+            crane.move_crates(self, step)
+        })
+    }
+
+    fn check(&mut self, step: &Step) -> Result<(), StackError> {
+        self.stack_mut(step.to)?;
+        let available = self.stack_mut(step.from)?.len();
+
+        if step.count > available {
+            return Err(StackError::NotEnoughCrates {
+                stack: step.from,
+                requested: step.count,
+                available,
+            });
+        }
+        Ok(())
+    }
+
+    /// Takes the top `count` crates off a stack, bottom one first
+    pub fn take(&mut self, stack: usize, count: usize) -> Result<Vec<char>, StackError> {
+        let crates = self.stack_mut(stack)?;
+
+        if count > crates.len() {
+            return Err(StackError::NotEnoughCrates {
+                stack,
+                requested: count,
+                available: crates.len(),
+            });
+        }
+
+        Ok(crates.split_off(crates.len() - count))
+    }
+
+    /// Puts the crates on a stack, the first one lowest
+    pub fn put(
+        &mut self,
+        stack: usize,
+        crates: impl IntoIterator<Item = char>,
+    ) -> Result<(), StackError> {
+        self.stack_mut(stack)?.extend(crates);
+        Ok(())
+    }
+
+    fn stack_mut(&mut self, stack: usize) -> Result<&mut Vec<char>, StackError> {
+        stack
+            .checked_sub(1)
+            .and_then(|i| self.stacks.get_mut(i))
+            .ok_or(StackError::UnknownStack(stack))
+    }
+
+    /// The top crate of every stack, empty stacks are skipped
+    pub fn top_row(&self) -> String {
+        self.stacks.iter().filter_map(|s| s.last()).collect()
+    }
+}
+
+/// Draws the stacks like the puzzle does, without trailing spaces
+impl Display for Stacks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let height = self.stacks.iter().map(|s| s.len()).max().unwrap_or(0);
+
+        for row in (0..height).rev() {
+            let line: Vec<String> = self
+                .stacks
+                .iter()
+                .map(|s| match s.get(row) {
+                    Some(name) => format!("[{}]", name),
+                    None => "   ".to_owned(),
+                })
+                .collect();
+            writeln!(f, "{}", line.join(" ").trim_end())?;
+        }
+
+        let numbers: Vec<String> = (1..=self.stacks.len())
+            .map(|i| format!(" {} ", i))
+            .collect();
+        writeln!(f, "{}", numbers.join(" ").trim_end())
+    }
+}
+
+pub trait Crane {
+    fn move_crates(&self, stacks: &mut Stacks, step: &Step) -> Result<(), StackError>;
+}
+
+/// Moves one crate at a time, so the moved crates end up in reverse order
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn move_crates(&self, stacks: &mut Stacks, step: &Step) -> Result<(), StackError> {
+        let crates = stacks.take(step.from, step.count)?;
+        stacks.put(step.to, crates.into_iter().rev())
+    }
+}
+
+/// Moves all crates of a step at once, so they keep their order
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn move_crates(&self, stacks: &mut Stacks, step: &Step) -> Result<(), StackError> {
+        let crates = stacks.take(step.from, step.count)?;
+        stacks.put(step.to, crates)
+    }
+}
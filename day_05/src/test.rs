@@ -1,5 +1,6 @@
 #[cfg(test)]
 mod test {
+    use crate::stacks::{CrateMover9000, CrateMover9001, StackError, Stacks, Step};
     use crate::{parse, part_1, part_2, Runner};
     use aoc_helper::runner::ProcessAndAssert;
 
@@ -22,4 +23,41 @@ mod test {
     fn part_2_works_for_input() {
         Runner::from_input_file(parse).process_and_assert(part_2, "SSCGWJCRB".to_owned())
     }
+
+    #[test]
+    fn parses_ragged_drawings_and_draws_them_back() {
+        let drawing = ["    [D]", "[N] [C]", "[Z] [M] [P]", " 1   2   3"];
+        let stacks = Stacks::parse(&drawing).unwrap();
+
+        assert_eq!(stacks.top_row(), "NDP");
+        assert_eq!(stacks.to_string(), drawing.join("\n") + "\n");
+
+        assert!(Stacks::parse(&["[A]", "", " 1"]).is_err());
+        assert!(Stacks::parse(&["[A] [B]", " 1"]).is_err());
+    }
+
+    #[test]
+    fn reports_impossible_moves() {
+        let mut stacks = Stacks::parse(&["[A]", " 1   2"]).unwrap();
+        let step = |line: &str| Step::parse(line).unwrap();
+
+        assert_eq!(
+            stacks.apply(
+                &CrateMover9000,
+                &[step("move 1 from 1 to 2"), step("move 2 from 2 to 1")]
+            ),
+            Err(StackError::NotEnoughCrates {
+                stack: 2,
+                requested: 2,
+                available: 1
+            })
+        );
+        assert_eq!(stacks.top_row(), "A");
+        assert_eq!(
+            stacks.apply(&CrateMover9001, &[step("move 1 from 2 to 3")]),
+            Err(StackError::UnknownStack(3))
+        );
+        assert_eq!(stacks.top_row(), "A");
+        assert!(Step::parse("move one from 1 to 2").is_err());
+    }
 }
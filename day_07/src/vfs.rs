@@ -0,0 +1,303 @@
+use std::{cell::OnceCell, collections::BTreeMap, fmt::Display};
+
+/// Points to a folder in a `FileSystem`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FolderId(usize);
+
+pub struct Folder {
+    name: String,
+    parent: Option<FolderId>,
+    folders: BTreeMap<String, FolderId>,
+    files: BTreeMap<String, u64>,
+}
+
+impl Folder {
+    pub fn files(&self) -> impl Iterator<Item = (&str, u64)> {
+        self.files.iter().map(|(name, size)| (name.as_str(), *size))
+    }
+
+    pub fn folders(&self) -> impl Iterator<Item = FolderId> + '_ {
+        self.folders.values().copied()
+    }
+}
+
+/// A folder tree as seen through the `cd` and `ls` commands of a terminal transcript
+/// The folder sizes are calculated once, and again only after the tree changed
+pub struct FileSystem {
+    folders: Vec<Folder>,
+    sizes: OnceCell<Vec<u64>>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct TranscriptError {
+    /// Line number in the transcript, starting at 1
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for TranscriptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl FileSystem {
+    pub fn new() -> Self {
+        Self {
+            folders: vec![Folder {
+                name: "/".to_owned(),
+                parent: None,
+                folders: BTreeMap::new(),
+                files: BTreeMap::new(),
+            }],
+            sizes: OnceCell::new(),
+        }
+    }
+
+    pub fn from_transcript(transcript: &str) -> Result<Self, TranscriptError> {
+        let mut file_system = FileSystem::new();
+        let mut current = file_system.root();
+        let mut listing = false;
+
+        for (index, line) in transcript.lines().enumerate() {
+            let error = |message: &str| TranscriptError {
+                line: index + 1,
+                message: message.to_owned(),
+            };
+
+            match line.split_whitespace().collect::<Vec<&str>>()[..] {
+                ["$", "ls"] => listing = true,
+                ["$", "cd", path] => {
+                    listing = false;
+                    current = file_system
+                        .change_directory(current, path)
+                        .ok_or_else(|| error("cd goes above the root folder"))?;
+                }
+                ["$", ..] => return Err(error("unknown command")),
+                _ if !listing => return Err(error("output without an ls command")),
+                ["dir", name] => {
+                    file_system.create_folder(current, name);
+                }
+                [size, name] => {
+                    let size = size.parse().map_err(|_| error("invalid file size"))?;
+                    file_system.create_file(current, name, size);
+                }
+                _ => return Err(error("invalid ls output")),
+            }
+        }
+
+        Ok(file_system)
+    }
+
+    pub fn root(&self) -> FolderId {
+        FolderId(0)
+    }
+
+    pub fn folder(&self, id: FolderId) -> &Folder {
+        &self.folders[id.0]
+    }
+
+    /// Adds the folder, or returns the existing one with that name
+    pub fn create_folder(&mut self, parent: FolderId, name: &str) -> FolderId {
+        if let Some(id) = self.folders[parent.0].folders.get(name) {
+            return *id;
+        }
+
+        let id = FolderId(self.folders.len());
+        self.folders.push(Folder {
+            name: name.to_owned(),
+            parent: Some(parent),
+            folders: BTreeMap::new(),
+            files: BTreeMap::new(),
+        });
+        self.folders[parent.0].folders.insert(name.to_owned(), id);
+        self.sizes = OnceCell::new();
+        id
+    }
+
+    /// Adds the file, listing the same file again replaces it instead of counting it twice
+    pub fn create_file(&mut self, parent: FolderId, name: &str, size: u64) {
+        self.folders[parent.0].files.insert(name.to_owned(), size);
+        self.sizes = OnceCell::new();
+    }
+
+    /// Follows the path like `cd` does, creating folders that were not listed yet
+    /// Returns None when the path goes above the root
+    pub fn change_directory(&mut self, from: FolderId, path: &str) -> Option<FolderId> {
+        let mut current = if path.starts_with('/') {
+            self.root()
+        } else {
+            from
+        };
+
+        for part in path.split('/').filter(|p| !p.is_empty() && *p != ".") {
+            current = match part {
+                ".." => self.folder(current).parent?,
+                name => self.create_folder(current, name),
+            };
+        }
+
+        Some(current)
+    }
+
+    pub fn path(&self, id: FolderId) -> String {
+        match self.folder(id).parent {
+            None => "/".to_owned(),
+            Some(parent) if parent == self.root() => format!("/{}", self.folder(id).name),
+            Some(parent) => format!("{}/{}", self.path(parent), self.folder(id).name),
+        }
+    }
+
+    /// The size of all files in the folder and its sub folders
+    pub fn size(&self, id: FolderId) -> u64 {
+        self.sizes.get_or_init(|| self.calculate_sizes())[id.0]
+    }
+
+    fn calculate_sizes(&self) -> Vec<u64> {
+        let mut sizes: Vec<u64> = self
+            .folders
+            .iter()
+            .map(|f| f.files.values().sum())
+            .collect();
+
+        // children are always created after their parent, so going backwards handles them first
+        for (index, folder) in self.folders.iter().enumerate().rev() {
+            if let Some(parent) = folder.parent {
+                sizes[parent.0] += sizes[index];
+            }
+        }
+
+        sizes
+    }
+
+    /// All folders, depth first starting with the root
+    pub fn directories(&self) -> impl Iterator<Item = FolderId> + '_ {
+        let mut stack = vec![self.root()];
+
+        std::iter::from_fn(move || {
+            let id = stack.pop()?;
+            stack.extend(self.folder(id).folders.values().rev());
+            Some(id)
+        })
+    }
+
+    /// Every folder with its size, largest first, like `du` piped through `sort`
+    pub fn du(&self) -> Vec<(String, u64)> {
+        let mut list: Vec<(String, u64)> = self
+            .directories()
+            .map(|id| (self.path(id), self.size(id)))
+            .collect();
+        list.sort_by(|(a_path, a_size), (b_path, b_size)| {
+            b_size.cmp(a_size).then(a_path.cmp(b_path))
+        });
+        list
+    }
+
+    fn print(
+        &self,
+        id: FolderId,
+        depth: usize,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        let folder = self.folder(id);
+        writeln!(f, "{}- {} (dir)", "  ".repeat(depth), folder.name)?;
+
+        for (name, size) in folder.files() {
+            writeln!(
+                f,
+                "{}- {} (file, size={})",
+                "  ".repeat(depth + 1),
+                name,
+                size
+            )?;
+        }
+
+        for child in folder.folders() {
+            self.print(child, depth + 1, f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for FileSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for FileSystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.print(self.root(), 0, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transcript(lines: &[&str]) -> Result<FileSystem, TranscriptError> {
+        FileSystem::from_transcript(&lines.join("\n"))
+    }
+
+    #[test]
+    fn handles_cd_root_absolute_paths_and_repeated_ls() {
+        let file_system = transcript(&[
+            "$ cd /",
+            "$ ls",
+            "dir a",
+            "100 b.txt",
+            "$ cd a",
+            "$ ls",
+            "20 c.txt",
+            "$ cd /",
+            "$ ls",
+            "dir a",
+            "100 b.txt",
+            "$ cd /a/d/e",
+            "$ ls",
+            "5 f.txt",
+            "$ cd ../../..",
+            "$ cd a",
+            "$ ls",
+            "20 c.txt",
+        ])
+        .unwrap();
+
+        assert_eq!(file_system.size(file_system.root()), 125);
+        assert_eq!(
+            file_system.du(),
+            vec![
+                ("/".to_owned(), 125),
+                ("/a".to_owned(), 25),
+                ("/a/d".to_owned(), 5),
+                ("/a/d/e".to_owned(), 5),
+            ]
+        );
+        assert_eq!(file_system.directories().count(), 4);
+    }
+
+    #[test]
+    fn size_is_updated_after_changes() {
+        let mut file_system = FileSystem::new();
+        let root = file_system.root();
+        file_system.create_file(root, "a", 10);
+        assert_eq!(file_system.size(root), 10);
+
+        let folder = file_system.create_folder(root, "b");
+        file_system.create_file(folder, "c", 5);
+        assert_eq!(file_system.size(root), 15);
+    }
+
+    #[test]
+    fn reports_malformed_transcripts() {
+        let error = |lines: &[&str]| transcript(lines).err().map(|e| e.line);
+
+        assert_eq!(error(&["$ cd /", "14848514 b.txt"]), Some(2));
+        assert_eq!(error(&["$ cd /", "$ cd .."]), Some(2));
+        assert_eq!(error(&["$ ls", "abc b.txt"]), Some(2));
+        assert_eq!(error(&["$ ls", "1 2 3"]), Some(2));
+        assert_eq!(error(&["$ rm -rf /"]), Some(1));
+        assert_eq!(error(&["$ cd"]), Some(1));
+    }
+}
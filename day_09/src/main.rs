@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use aoc_helper::{collections::SparseGrid, vectors::Vec2D};
 
 fn main() {
     let input = parse_input("input.txt");
@@ -7,14 +7,17 @@ fn main() {
     println!("part 2: {}", part_2(&input));
 }
 
-fn parse_input(filename: &str) -> Vec<(char, u32)> {
+fn parse_input(filename: &str) -> Vec<Move> {
     let input = std::fs::read_to_string(filename).expect("Could not read file");
 
     input
         .lines()
         .map(|l| {
-            if let [dir, len] = l.split(' ').collect::<Vec<&str>>()[..] {
-                (dir.chars().next().unwrap(), len.parse().unwrap())
+            if let [direction, steps] = l.split(' ').collect::<Vec<&str>>()[..] {
+                Move {
+                    direction: parse_direction(direction),
+                    steps: steps.parse().expect("invalid step count"),
+                }
             } else {
                 panic!("invalid command")
             }
@@ -22,52 +25,96 @@ fn parse_input(filename: &str) -> Vec<(char, u32)> {
         .collect()
 }
 
-fn part_1(moves: &Vec<(char, u32)>) -> usize {
-    simulate(moves, 2)
+/// `U`, `D`, `L` and `R`, or one vertical and one horizontal for a diagonal like `UL`
+fn parse_direction(string: &str) -> Vec2D {
+    let mut horizontal = None;
+    let mut vertical = None;
+
+    for c in string.chars() {
+        let (axis, step) = match c {
+            'U' => (&mut vertical, -1),
+            'D' => (&mut vertical, 1),
+            'L' => (&mut horizontal, -1),
+            'R' => (&mut horizontal, 1),
+            _ => panic!("invalid direction: {}", string),
+        };
+        if axis.replace(step).is_some() {
+            panic!("more than one step along an axis: {}", string);
+        }
+    }
+
+    if horizontal.is_none() && vertical.is_none() {
+        panic!("empty direction");
+    }
+    Vec2D::new(horizontal.unwrap_or(0), vertical.unwrap_or(0))
+}
+
+fn part_1(moves: &[Move]) -> usize {
+    trail(moves, 2).len()
 }
 
-fn part_2(moves: &Vec<(char, u32)>) -> usize {
-    simulate(moves, 10)
+fn part_2(moves: &[Move]) -> usize {
+    trail(moves, 10).len()
 }
 
-fn simulate(moves: &Vec<(char, u32)>, count: usize) -> usize {
-    let mut knots = vec![(0, 0); count - 1];
-    let mut head = (0, 0);
-
-    let mut visited_positions: HashMap<(i32, i32), ()> = HashMap::new();
-    visited_positions.insert(*knots.last().unwrap(), ());
-
-    for (direction, length) in moves {
-        for _ in 0..*length {
-            match direction {
-                'U' => head.1 += 1,
-                'D' => head.1 -= 1,
-                'L' => head.0 -= 1,
-                'R' => head.0 += 1,
-                _ => panic!("invalid direction"),
-            }
+struct Move {
+    direction: Vec2D,
+    steps: u32,
+}
+
+#[derive(Clone, Debug)]
+struct Rope {
+    /// The head first
+    knots: Vec<Vec2D>,
+}
+
+impl Rope {
+    fn new(count: usize) -> Self {
+        assert!(count > 0, "A rope needs at least one knot");
+        Self {
+            knots: vec![Vec2D::new(0, 0); count],
+        }
+    }
 
-            let mut prev_knot = head;
+    fn tail(&self) -> Vec2D {
+        *self.knots.last().unwrap()
+    }
 
-            for mut knot in knots.iter_mut() {
-                let x_diff: i32 = prev_knot.0 - knot.0;
-                let y_diff: i32 = prev_knot.1 - knot.1;
+    /// Moves the head one step, every other knot follows the knot before it
+    /// when they are no longer touching
+    fn step(&mut self, direction: Vec2D) {
+        self.knots[0] = self.knots[0] + direction;
 
-                if x_diff.abs() > 1 || y_diff.abs() > 1 {
-                    knot.0 += x_diff.signum();
-                    knot.1 += y_diff.signum();
-                }
+        for i in 1..self.knots.len() {
+            let difference = self.knots[i - 1] - self.knots[i];
 
-                prev_knot = *knot;
+            if difference.x.abs() <= 1 && difference.y.abs() <= 1 {
+                break;
             }
 
-            visited_positions
-                .entry(*knots.last().unwrap())
-                .or_insert_with(|| ());
+            self.knots[i] =
+                self.knots[i] + Vec2D::new(difference.x.signum(), difference.y.signum());
         }
     }
+}
+
+/// The rope after every single step of the moves
+fn states(moves: &[Move], count: usize) -> impl Iterator<Item = Rope> + '_ {
+    let steps = moves
+        .iter()
+        .flat_map(|m| std::iter::repeat_n(m.direction, m.steps as usize));
+
+    steps.scan(Rope::new(count), |rope, direction| {
+        rope.step(direction);
+        Some(rope.clone())
+    })
+}
 
-    visited_positions.len()
+/// Every position the tail visited, including the start
+fn trail(moves: &[Move], count: usize) -> SparseGrid {
+    std::iter::once(Vec2D::new(0, 0))
+        .chain(states(moves, count).map(|rope| rope.tail()))
+        .collect()
 }
 
 #[cfg(test)]
@@ -85,4 +132,42 @@ mod tests {
         let input = parse_input("test.txt");
         assert_eq!(part_2(&input), 1);
     }
+
+    #[test]
+    fn draws_the_trail() {
+        let input = parse_input("test.txt");
+        assert_eq!(
+            trail(&input, 2).to_string(),
+            "..##.\n...##\n.####\n....#\n####.\n"
+        );
+
+        let last = states(&input, 10).last().unwrap();
+        assert_eq!(last.knots[0], Vec2D::new(2, -2));
+        assert_eq!(last.tail(), Vec2D::new(0, 0));
+    }
+
+    #[test]
+    fn moves_diagonally() {
+        let moves = vec![Move {
+            direction: parse_direction("UR"),
+            steps: 3,
+        }];
+        let last = states(&moves, 3).last().unwrap();
+        assert_eq!(
+            last.knots,
+            vec![Vec2D::new(3, -3), Vec2D::new(2, -2), Vec2D::new(1, -1)]
+        );
+        assert_eq!(parse_direction("LD"), Vec2D::new(-1, 1));
+    }
+
+    #[test]
+    fn rejects_two_steps_along_one_axis() {
+        for direction in ["UD", "UU", "LR", "ULD", ""] {
+            assert!(
+                std::panic::catch_unwind(|| parse_direction(direction)).is_err(),
+                "{}",
+                direction
+            );
+        }
+    }
 }
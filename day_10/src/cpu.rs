@@ -0,0 +1,224 @@
+use std::{collections::HashMap, fmt::Display};
+
+/// What an instruction does, registered by name in an `InstructionTable`
+#[derive(Clone, Copy)]
+pub struct Opcode {
+    pub cycles: usize,
+    /// The number of arguments the instruction takes
+    pub arguments: usize,
+    /// The new value of X once the last cycle is done, from the old value and the arguments
+    pub execute: fn(i64, &[i64]) -> i64,
+}
+
+#[derive(Clone)]
+pub struct Instruction {
+    opcode: Opcode,
+    arguments: Vec<i64>,
+}
+
+impl Instruction {
+    pub fn cycles(&self) -> usize {
+        self.opcode.cycles
+    }
+
+    pub fn execute(&self, x: i64) -> i64 {
+        (self.opcode.execute)(x, &self.arguments)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: String,
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: '{}'", self.message, self.line)
+    }
+}
+
+/// The opcodes a program can use, by name
+pub struct InstructionTable {
+    opcodes: HashMap<String, Opcode>,
+}
+
+/// The opcodes of the puzzle, `noop` and `addx`
+impl Default for InstructionTable {
+    fn default() -> Self {
+        let mut table = Self::new();
+        table
+            .register(
+                "noop",
+                Opcode {
+                    cycles: 1,
+                    arguments: 0,
+                    execute: |x, _| x,
+                },
+            )
+            .register(
+                "addx",
+                Opcode {
+                    cycles: 2,
+                    arguments: 1,
+                    execute: |x, arguments| x + arguments[0],
+                },
+            );
+        table
+    }
+}
+
+impl InstructionTable {
+    /// A table without any opcodes
+    pub fn new() -> Self {
+        Self {
+            opcodes: HashMap::new(),
+        }
+    }
+
+    /// Adds an opcode, or replaces the one with the same name
+    pub fn register(&mut self, name: &str, opcode: Opcode) -> &mut Self {
+        self.opcodes.insert(name.to_owned(), opcode);
+        self
+    }
+
+    pub fn parse(&self, line: &str) -> Result<Instruction, ParseError> {
+        let error = |message: &str| ParseError {
+            line: line.to_owned(),
+            message: message.to_owned(),
+        };
+
+        let mut parts = line.split_whitespace();
+        let name = parts.next().ok_or_else(|| error("empty line"))?;
+        let arguments = parts
+            .map(|a| a.parse())
+            .collect::<Result<Vec<i64>, _>>()
+            .map_err(|_| error("invalid argument"))?;
+
+        let opcode = *self
+            .opcodes
+            .get(name)
+            .ok_or_else(|| error("unknown opcode"))?;
+        if arguments.len() != opcode.arguments {
+            return Err(error("wrong number of arguments"));
+        }
+
+        Ok(Instruction { opcode, arguments })
+    }
+}
+
+/// The value of X during a cycle, cycles count from 1
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CycleState {
+    pub cycle: usize,
+    pub x: i64,
+}
+
+/// Something that looks at every cycle while the program runs
+pub trait Hook {
+    fn on_cycle(&mut self, state: CycleState);
+}
+
+/// Runs a program and yields the state of every cycle
+pub struct Cpu<'a> {
+    program: &'a [Instruction],
+    x: i64,
+    cycle: usize,
+    /// The instruction being executed
+    current: usize,
+    /// How many cycles of the current instruction are done
+    cycles_done: usize,
+}
+
+impl<'a> Cpu<'a> {
+    pub fn new(program: &'a [Instruction]) -> Self {
+        Self {
+            program,
+            x: 1,
+            cycle: 0,
+            current: 0,
+            cycles_done: 0,
+        }
+    }
+
+    /// Runs the whole program, showing every cycle to the hooks
+    pub fn run_with_hooks(self, hooks: &mut [&mut dyn Hook]) {
+        for state in self {
+            for hook in hooks.iter_mut() {
+                hook.on_cycle(state);
+            }
+        }
+    }
+}
+
+impl Iterator for Cpu<'_> {
+    type Item = CycleState;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // instructions without cycles take effect right away
+        while self.program.get(self.current)?.cycles() == 0 {
+            self.x = self.program[self.current].execute(self.x);
+            self.current += 1;
+        }
+
+        let instruction = &self.program[self.current];
+        self.cycle += 1;
+        let state = CycleState {
+            cycle: self.cycle,
+            x: self.x,
+        };
+
+        self.cycles_done += 1;
+        if self.cycles_done == instruction.cycles() {
+            self.x = instruction.execute(self.x);
+            self.current += 1;
+            self.cycles_done = 0;
+        }
+
+        Some(state)
+    }
+}
+
+/// Adds up cycle * X for the 20th cycle and every 40 cycles after it
+#[derive(Default)]
+pub struct SignalStrength {
+    pub total: i64,
+}
+
+impl Hook for SignalStrength {
+    fn on_cycle(&mut self, state: CycleState) {
+        if state.cycle >= 20 && (state.cycle - 20).is_multiple_of(40) {
+            self.total += state.cycle as i64 * state.x;
+        }
+    }
+}
+
+/// Draws one pixel per cycle, lit when the three pixel wide sprite at X covers it
+pub struct Crt {
+    width: usize,
+    pub screen: String,
+}
+
+impl Crt {
+    pub fn new(width: usize) -> Self {
+        Self {
+            width,
+            screen: String::new(),
+        }
+    }
+}
+
+impl Hook for Crt {
+    fn on_cycle(&mut self, state: CycleState) {
+        let column = ((state.cycle - 1) % self.width) as i64;
+
+        match (column - state.x).abs() <= 1 {
+            true => self.screen.push('█'),
+            false => self.screen.push(' '),
+        }
+
+        if column as usize == self.width - 1 {
+            self.screen.push('\n');
+        }
+    }
+}
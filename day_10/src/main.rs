@@ -1,3 +1,7 @@
+mod cpu;
+
+use cpu::{Cpu, Crt, Instruction, InstructionTable, SignalStrength};
+
 fn main() {
     let input = read_input("input.txt");
 
@@ -5,90 +9,30 @@ fn main() {
     println!("part 2: \n{}", part_2(&input));
 }
 
-fn read_input(filename: &str) -> Vec<String> {
+fn read_input(filename: &str) -> Vec<Instruction> {
     let text = std::fs::read_to_string(filename).expect("File not found");
-    text.lines().map(|l| l.to_owned()).collect()
-}
-
-fn part_1(commands: &[String]) -> i64 {
-    let mut x = 1i64;
-    let mut tick = 0u32;
-    let mut score = 0i64;
-
-    for command in commands {
-        match command.split(' ').collect::<Vec<&str>>()[..] {
-            ["noop"] => {
-                score += perform_tick_1(&mut tick, &x);
-            }
-            ["addx", number] => {
-                let number = number.parse::<i64>().expect("not a number");
-                for _ in 0..2 {
-                    score += perform_tick_1(&mut tick, &x);
-                }
-                x += number;
-            }
-            _ => panic!("unknown command"),
-        }
-    }
-
-    score
-}
-
-fn perform_tick_1(tick: &mut u32, x: &i64) -> i64 {
-    let nth_tick: i64 = (*tick + 1).into();
-
-    *tick += 1;
-
-    if nth_tick == 20 || (nth_tick - 20) % 40 == 0 {
-        return nth_tick * *x;
-    }
-
-    0
+    let table = InstructionTable::default();
+    text.lines()
+        .map(|l| table.parse(l).unwrap_or_else(|e| panic!("{}", e)))
+        .collect()
 }
 
-fn part_2(commands: &[String]) -> String {
-    let mut x = 1i64;
-    let mut tick = 0u32;
-    let mut result: String = "".to_owned();
-
-    for command in commands {
-        match command.split(' ').collect::<Vec<&str>>()[..] {
-            ["noop"] => {
-                perform_tick_2(&mut tick, &x, &mut result);
-            }
-            ["addx", number] => {
-                let number = number.parse::<i64>().expect("not a number");
-                for _ in 0..2 {
-                    perform_tick_2(&mut tick, &x, &mut result);
-                }
-                x += number;
-            }
-            _ => panic!("unknown command"),
-        }
-    }
-
-    result
+fn part_1(program: &[Instruction]) -> i64 {
+    let mut signal = SignalStrength::default();
+    Cpu::new(program).run_with_hooks(&mut [&mut signal]);
+    signal.total
 }
 
-fn perform_tick_2(tick: &mut u32, x: &i64, result: &mut String) {
-    let index: i64 = (*tick % 40).into();
-    
-    if index - 1 == *x || index == *x || index + 1 == *x {
-        result.push('█');
-    } else {
-        result.push(' ');
-    }
-    
-    *tick += 1;
-
-    if *tick % 40u32 == 0 {
-        result.push('\n');
-    }
+fn part_2(program: &[Instruction]) -> String {
+    let mut crt = Crt::new(40);
+    Cpu::new(program).run_with_hooks(&mut [&mut crt]);
+    crt.screen
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use cpu::{CycleState, Opcode};
 
     #[test]
     fn part_1_works() {
@@ -110,4 +54,29 @@ mod tests {
 "
         );
     }
+
+    #[test]
+    fn runs_registered_opcodes() {
+        let mut table = InstructionTable::default();
+        table.register(
+            "mulx",
+            Opcode {
+                cycles: 3,
+                arguments: 1,
+                execute: |x, arguments| x * arguments[0],
+            },
+        );
+
+        let program: Vec<Instruction> = ["addx 2", "mulx 4", "noop"]
+            .iter()
+            .map(|l| table.parse(l).unwrap())
+            .collect();
+        let values: Vec<i64> = Cpu::new(&program).map(|s: CycleState| s.x).collect();
+        assert_eq!(values, vec![1, 1, 3, 3, 3, 12]);
+
+        assert!(table.parse("divx 2").is_err());
+        assert!(table.parse("addx").is_err());
+        assert!(table.parse("addx a").is_err());
+        assert!(InstructionTable::new().parse("noop").is_err());
+    }
 }
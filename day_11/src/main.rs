@@ -1,100 +1,172 @@
-fn main() {
-    let mut monkeys = read_input("input.txt");
-    println!("part 1: {}", part_1(&mut monkeys));
+use aoc_helper::{
+    math,
+    runner::{ProcessOwnedAndWrite, Runner},
+};
 
-    let mut monkeys = read_input("input.txt");
-    println!("part 2: {}", part_2(&mut monkeys));
+fn main() {
+    let runner = Runner::from_input_file(parse);
+    runner.process_owned_and_write_part_1(part_1);
+    runner.process_owned_and_write_part_2(part_2);
 }
 
-fn read_input(filename: &str) -> Vec<Monkey> {
-    let string = std::fs::read_to_string(filename).expect("File not found");
-
+fn parse(string: String) -> Vec<Monkey> {
     let lines = string.lines().collect::<Vec<&str>>();
     let monkey_inputs = lines.split(|l| l.is_empty()).collect::<Vec<&[&str]>>();
 
     monkey_inputs.iter().map(|m| Monkey::new(m)).collect()
 }
 
-fn part_1(monkeys: &mut Vec<Monkey>) -> u64 {
-    calc(monkeys, 20, true)
+fn part_1(mut monkeys: Vec<Monkey>) -> u64 {
+    let game = Game {
+        rounds: 20,
+        relief: divide_by(3),
+    };
+    monkey_business(&play(&mut monkeys, &game))
 }
 
-fn part_2(monkeys: &mut Vec<Monkey>) -> u64 {
-    calc(monkeys, 10000, false)
+fn part_2(mut monkeys: Vec<Monkey>) -> u64 {
+    let game = Game {
+        rounds: 10000,
+        relief: modulo_of_all_tests(&monkeys),
+    };
+    monkey_business(&play(&mut monkeys, &game))
 }
 
-fn calc(monkeys: &mut Vec<Monkey>, turns: usize, divide: bool) -> u64 {
+/// What happens to the worry level after a monkey inspected an item
+type Relief = Box<dyn Fn(u64) -> u64>;
 
-    let modulus: u64 = monkeys.iter().map(|m| m.divisible_test).product();
-
-    for _ in 0..turns {
-        for i in 0..monkeys.len() {
-            let active_monkey = &mut monkeys[i];
+fn divide_by(divisor: u64) -> Relief {
+    Box::new(move |worry| worry / divisor)
+}
 
-            let mut moved_items = vec![];
+/// Keeps the worry levels small, without changing the outcome of any test
+fn modulo_of_all_tests(monkeys: &[Monkey]) -> Relief {
+    let modulus = monkeys
+        .iter()
+        .map(|m| m.divisible_test)
+        .fold(1, math::lcm);
+    Box::new(move |worry| worry % modulus)
+}
 
-            for item in &active_monkey.items {
+struct Game {
+    rounds: usize,
+    relief: Relief,
+}
 
-                let var_1 = parse_var(&active_monkey.operation[0], item);
-                let var_2 = parse_var(&active_monkey.operation[2], item);
+struct RoundStats {
+    /// Items inspected by each monkey during the round
+    inspections: Vec<usize>,
+    /// Highest worry level thrown during the round
+    max_worry: u64,
+}
 
-                let mut new_value = calculate_new_value(&active_monkey.operation[1], var_1 % modulus, var_2 % modulus);
+fn play(monkeys: &mut [Monkey], game: &Game) -> Vec<RoundStats> {
+    (0..game.rounds)
+        .map(|_| play_round(monkeys, &game.relief))
+        .collect()
+}
 
-                if divide{
-                    new_value /= 3;
-                }
+fn play_round(monkeys: &mut [Monkey], relief: &Relief) -> RoundStats {
+    let mut stats = RoundStats {
+        inspections: vec![0; monkeys.len()],
+        max_worry: 0,
+    };
 
-                let throw_to_id = match new_value % active_monkey.divisible_test == 0 {
-                    true => active_monkey.true_monkey_id,
-                    false => active_monkey.false_monkey_id,
-                };
+    for i in 0..monkeys.len() {
+        let items = std::mem::take(&mut monkeys[i].items);
+        stats.inspections[i] = items.len();
 
-                moved_items.push((throw_to_id, new_value));
-            }
+        for item in items {
+            let monkey = &monkeys[i];
+            let new_value = relief(monkey.operation.apply(item));
 
-            active_monkey.inspection_count += active_monkey.items.len();
-            active_monkey.items = vec![];
+            let throw_to_id = match new_value.is_multiple_of(monkey.divisible_test) {
+                true => monkey.true_monkey_id,
+                false => monkey.false_monkey_id,
+            };
 
-            for moved_item in moved_items {
-                monkeys[moved_item.0].items.push(moved_item.1);
-            }
+            stats.max_worry = stats.max_worry.max(new_value);
+            monkeys[throw_to_id].items.push(new_value);
         }
     }
-    let mut scores = monkeys
-        .iter()
-        .map(|m| m.inspection_count)
-        .collect::<Vec<usize>>();
+
+    stats
+}
+
+/// The inspections of the two most active monkeys multiplied
+fn monkey_business(stats: &[RoundStats]) -> u64 {
+    let mut scores = total_inspections(stats);
     scores.sort_unstable();
-    if let [item1, item2] = scores.iter().rev().take(2).collect::<Vec<&usize>>()[..] {
-        let item1: u64 = (*item1).try_into().unwrap();
-        let item2: u64 = (*item2).try_into().unwrap();
-        return item1 * item2;
+    scores.iter().rev().take(2).map(|s| *s as u64).product()
+}
+
+fn total_inspections(stats: &[RoundStats]) -> Vec<usize> {
+    let mut totals = vec![0; stats.first().map_or(0, |s| s.inspections.len())];
+    for round in stats {
+        for (total, count) in totals.iter_mut().zip(&round.inspections) {
+            *total += count;
+        }
     }
-    panic!()
+    totals
 }
 
-fn calculate_new_value(operation: &str, var_1: u64, var_2: u64) -> u64 {
-    match operation {
-        "+" => var_1 + var_2,
-        "*" => var_1 * var_2,
-        _ => panic!("Invalid operator"),
+#[derive(Clone, Copy)]
+enum Operand {
+    Old,
+    Number(u64),
+}
+
+impl Operand {
+    fn parse(string: &str) -> Self {
+        match string {
+            "old" => Operand::Old,
+            num => Operand::Number(num.parse().expect("Invalid operand")),
+        }
     }
+
+    fn value(&self, old: u64) -> u64 {
+        match self {
+            Operand::Old => old,
+            Operand::Number(num) => *num,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Operation {
+    Add(Operand, Operand),
+    Multiply(Operand, Operand),
 }
 
-fn parse_var(operation: &str, item: &u64) -> u64 {
-    match operation {
-        "old" => *item,
-        num => num.parse().unwrap(),
+impl Operation {
+    fn parse(string: &str) -> Self {
+        let [lhs, operator, rhs] = string.split(' ').collect::<Vec<&str>>()[..] else {
+            panic!("Invalid operation")
+        };
+        let (lhs, rhs) = (Operand::parse(lhs), Operand::parse(rhs));
+
+        match operator {
+            "+" => Operation::Add(lhs, rhs),
+            "*" => Operation::Multiply(lhs, rhs),
+            _ => panic!("Invalid operator"),
+        }
+    }
+
+    fn apply(&self, old: u64) -> u64 {
+        match self {
+            Operation::Add(lhs, rhs) => lhs.value(old) + rhs.value(old),
+            Operation::Multiply(lhs, rhs) => lhs.value(old) * rhs.value(old),
+        }
     }
 }
 
+#[derive(Clone)]
 struct Monkey {
     items: Vec<u64>,
-    operation: [String; 3],
+    operation: Operation,
     divisible_test: u64,
     true_monkey_id: usize,
     false_monkey_id: usize,
-    inspection_count: usize,
 }
 
 impl Monkey {
@@ -115,14 +187,7 @@ impl Monkey {
             .map(|s| s.parse().unwrap())
             .collect();
 
-        let operation = &lines[2]
-            .trim_start_matches("  Operation: new = ")
-            .split(' ')
-            .map(|s| s.to_owned())
-            .collect::<Vec<String>>()[..];
-
-        let operation: &[String; 3] = operation.try_into().unwrap();
-        let operation = operation.to_owned();
+        let operation = Operation::parse(lines[2].trim_start_matches("  Operation: new = "));
 
         let divisible_test = lines[3]
             .trim_start_matches("  Test: divisible by ")
@@ -145,7 +210,6 @@ impl Monkey {
             divisible_test,
             true_monkey_id,
             false_monkey_id,
-            inspection_count: 0,
         }
     }
 }
@@ -153,16 +217,34 @@ impl Monkey {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_helper::runner::ProcessOwnedAndAssert;
 
     #[test]
     fn part_1_works() {
-        let mut monkeys = read_input("test.txt");
-        assert_eq!(part_1(&mut monkeys), 10605);
+        Runner::from_test_file(parse).process_owned_and_assert(part_1, 10605)
     }
 
     #[test]
     fn part_2_works() {
-        let mut monkeys = read_input("test.txt");
-        assert_eq!(part_2(&mut monkeys), 2713310158);
+        Runner::from_test_file(parse).process_owned_and_assert(part_2, 2713310158)
+    }
+
+    #[test]
+    fn records_inspections_per_round() {
+        Runner::from_test_file(parse).process_owned_and_assert(
+            |mut monkeys| {
+                let game = Game {
+                    rounds: 20,
+                    relief: modulo_of_all_tests(&monkeys),
+                };
+                let stats = play(&mut monkeys, &game);
+                (
+                    stats[0].inspections.clone(),
+                    total_inspections(&stats),
+                    stats.iter().all(|s| s.max_worry < 23 * 19 * 13 * 17),
+                )
+            },
+            (vec![2, 4, 3, 6], vec![99, 97, 8, 103], true),
+        )
     }
 }
@@ -20,7 +20,7 @@ fn read_input(filename: &str) -> (Vec<Vec<i32>>, UPoint, UPoint) {
             l.chars()
                 .collect::<Vec<char>>()
         })
-        .collect();
+        .collect::<Vec<Vec<char>>>();
 
     let(start, end) = find_start_and_end(chars);
 
@@ -41,7 +41,7 @@ fn part_1(grid: Grid, start_index: UPoint, end_index: UPoint) -> u32 {
 
     let mut active_nodes = VecDeque::new();
     active_nodes.push_back(start_index);
-    let mut scores: Vec<Vec<u32>> = vec![vec![u32::max_value(); grid[0].len()]; grid.len()];
+    let mut scores: Vec<Vec<u32>> = vec![vec![u32::MAX; grid[0].len()]; grid.len()];
 
     scores[start_index.1][start_index.0] = 0;
 
@@ -81,7 +81,7 @@ fn part_1(grid: Grid, start_index: UPoint, end_index: UPoint) -> u32 {
 }
 
 fn part_2(grid: Grid, _: UPoint, end_index: UPoint) -> u32{
-    let mut min_score = u32::max_value();
+    let mut min_score = u32::MAX;
 
     for (y, line) in grid.iter().enumerate(){
         for (x, field) in line.iter().enumerate(){
@@ -97,7 +97,7 @@ fn part_2(grid: Grid, _: UPoint, end_index: UPoint) -> u32{
     min_score
 }
 
-fn find_start_and_end(grid: &Vec<Vec<char>>) -> (UPoint, UPoint) {
+fn find_start_and_end(grid: &[Vec<char>]) -> (UPoint, UPoint) {
     let mut start_index: UPoint = (0, 0);
     let mut end_index: UPoint = (0, 0);
 
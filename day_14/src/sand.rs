@@ -0,0 +1,180 @@
+use std::fmt::Display;
+
+use aoc_helper::vectors::Vec2D;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tile {
+    Air,
+    Rock,
+    Sand,
+}
+
+/// What is below the lowest rock
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Floor {
+    /// Sand that falls past the lowest rock is gone
+    Abyss,
+    /// A floor of rock two below the lowest rock, as wide as the sand can spread
+    Infinite,
+}
+
+/// A dense grid of tiles between two corners
+pub struct Grid {
+    tiles: Vec<Tile>,
+    min: Vec2D,
+    size: Vec2D,
+    source: Vec2D,
+}
+
+impl Grid {
+    fn new(min: Vec2D, max: Vec2D, source: Vec2D) -> Self {
+        let size = Vec2D::new(max.x - min.x + 1, max.y - min.y + 1);
+        Self {
+            tiles: vec![Tile::Air; (size.x * size.y) as usize],
+            min,
+            size,
+            source,
+        }
+    }
+
+    /// None outside the grid
+    pub fn get(&self, position: Vec2D) -> Option<Tile> {
+        self.index(position).map(|i| self.tiles[i])
+    }
+
+    fn set(&mut self, position: Vec2D, tile: Tile) {
+        let index = self.index(position).expect("Position outside the grid");
+        self.tiles[index] = tile;
+    }
+
+    fn index(&self, position: Vec2D) -> Option<usize> {
+        let relative = position - self.min;
+        relative
+            .is_in_bounds(self.size)
+            .then(|| (relative.y * self.size.x + relative.x) as usize)
+    }
+
+    fn draw_line(&mut self, from: Vec2D, to: Vec2D) {
+        let step = Vec2D::new((to.x - from.x).signum(), (to.y - from.y).signum());
+        let mut position = from;
+
+        self.set(position, Tile::Rock);
+        while position != to {
+            position = position + step;
+            self.set(position, Tile::Rock);
+        }
+    }
+}
+
+/// Draws the grid like the puzzle does, with `+` where the sand comes from
+impl Display for Grid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.size.y {
+            for x in 0..self.size.x {
+                let position = self.min + Vec2D::new(x, y);
+                let symbol = match self.get(position) {
+                    Some(Tile::Rock) => '#',
+                    Some(Tile::Sand) => 'o',
+                    _ if position == self.source => '+',
+                    _ => '.',
+                };
+                write!(f, "{}", symbol)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Drops grains of sand one at a time
+/// The path of the previous grain is kept, the next grain follows it up to the position
+/// before where the previous one came to rest, so it does not have to fall from the top again
+pub struct SandSimulator {
+    grid: Grid,
+    source: Vec2D,
+    path: Vec<Vec2D>,
+    grains: usize,
+}
+
+impl SandSimulator {
+    pub fn new(rocks: &[Vec<Vec2D>], source: Vec2D, floor: Floor) -> Self {
+        let points = || rocks.iter().flatten().chain([&source]);
+        let lowest = points().map(|p| p.y).max().unwrap();
+
+        // sand never spreads further sideways than it falls down
+        let (min, max) = match floor {
+            Floor::Abyss => (
+                Vec2D::new(points().map(|p| p.x).min().unwrap() - 1, 0),
+                Vec2D::new(points().map(|p| p.x).max().unwrap() + 1, lowest),
+            ),
+            Floor::Infinite => (
+                Vec2D::new(source.x - lowest - 2, 0),
+                Vec2D::new(source.x + lowest + 2, lowest + 2),
+            ),
+        };
+
+        let mut grid = Grid::new(min, max, source);
+        for line in rocks {
+            for pair in line.windows(2) {
+                grid.draw_line(pair[0], pair[1]);
+            }
+        }
+        if floor == Floor::Infinite {
+            grid.draw_line(Vec2D::new(min.x, max.y), max);
+        }
+
+        Self {
+            grid,
+            source,
+            path: vec![],
+            grains: 0,
+        }
+    }
+
+    /// The rocks and the sand at rest so far, displays like the drawings of the puzzle
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    /// The number of grains at rest
+    pub fn grains(&self) -> usize {
+        self.grains
+    }
+
+    /// Returns where the grain came to rest, or None when it fell into the abyss
+    /// or the source is blocked
+    pub fn drop_grain(&mut self) -> Option<Vec2D> {
+        if self.path.is_empty() {
+            if self.grid.get(self.source) != Some(Tile::Air) {
+                return None;
+            }
+            self.path.push(self.source);
+        }
+
+        loop {
+            let current = *self.path.last()?;
+            let next = [Vec2D::new(0, 1), Vec2D::new(-1, 1), Vec2D::new(1, 1)]
+                .into_iter()
+                .map(|step| current + step)
+                .find(|p| !matches!(self.grid.get(*p), Some(Tile::Rock | Tile::Sand)));
+
+            match next {
+                Some(position) if self.grid.get(position).is_none() => return None,
+                Some(position) => self.path.push(position),
+                None => {
+                    self.path.pop();
+                    self.grid.set(current, Tile::Sand);
+                    self.grains += 1;
+                    return Some(current);
+                }
+            }
+        }
+    }
+
+    /// Drops grains until one falls into the abyss or the source is blocked,
+    /// returns the number of grains at rest
+    pub fn fill(&mut self) -> usize {
+        while self.drop_grain().is_some() {}
+        self.grains()
+    }
+}
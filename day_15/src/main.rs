@@ -1,73 +1,57 @@
+use aoc_helper::{
+    collections::IntervalSet,
+    geometry::{self, Diamond},
+    runner::{ProcessWithParamsAndWrite, Runner},
+    vectors::Vec2D,
+};
 use std::fmt::Display;
 
 fn main() {
-    let sensors = read_input("input.txt");
+    let runner = Runner::from_input_file(parse).with_params(Params {
+        row: 2000000,
+        range: 4000000,
+    });
+    runner.process_with_params_and_write_part_1(part_1);
+    runner.process_with_params_and_write_part_2(part_2);
+}
 
-    println!("part 1: {}", part_1(&sensors, 2000000));
-    println!("part 2: {}", part_2(&sensors, 4000000));
+/// The puzzle uses different sizes for the example and the real input
+struct Params {
+    row: i32,
+    range: i32,
 }
 
-fn part_1(sensors: &[Sensor], row: i32) -> u32 {
-    let mut count = 0;
+fn part_1(sensors: &[Sensor], params: &Params) -> u64 {
+    let row = params.row;
 
-    // just choose some ridiculously large range, that should work
-    for x in -10000000..10000000 {
-        let point = Point::new(x, row);
+    let mut covered: IntervalSet = sensors
+        .iter()
+        .filter_map(|s| s.area.row_intersection(row))
+        .collect();
 
-        if !sensors.iter().all(|s| {
-            s.position.manhattan_distance(&point) > s.distance || s.closest_beacon == point
-        }) {
-            count += 1;
+    // a beacon that is already there is not a position where a beacon cannot be
+    for beacon in sensors.iter().map(|s| s.closest_beacon) {
+        if beacon.y == row {
+            covered.remove(beacon.x as i64..=beacon.x as i64);
         }
     }
 
-    count
+    covered.len()
 }
 
-/// So, I realise I should check the edges of all the rhombuses, and find where they all overlap
-/// But since brute forcing with jumps seems kinda doable, it's fun to see if it really is
-/// (turns out it kinda isn't, it took quite some hours to complete)
-fn part_2(sensors: &[Sensor], range: i32) -> i64 {
-    for y in 0..=range {
-        let mut x_iter = 0..=range;
-
-        while let Some(x) = x_iter.next() {
-            let point = Point::new(x, y);
-
-            if sensors
-                .iter()
-                .all(|s| s.position.manhattan_distance(&point) > s.distance)
-            {
-                return x as i64 * 4000000 + y as i64;
-            } else {
-                let max_delta = sensors
-                    .iter()
-                    .filter(|s| s.position.manhattan_distance(&point) <= s.distance)
-                    .map(|s| s.distance - s.position.manhattan_distance(&point))
-                    .max()
-                    .unwrap();
-
-                let skip: usize = max_delta.try_into().unwrap();
-
-                for _ in 0..skip {
-                    if x_iter.next().is_none(){
-                        break;
-                    }
-                }
-            }
-        }
+/// There is only one cell left, so it has to be right next to the edges of the sensor areas
+fn part_2(sensors: &[Sensor], params: &Params) -> i64 {
+    let areas: Vec<Diamond> = sensors.iter().map(|s| s.area).collect();
+    let bounds = Vec2D::new(params.range, params.range);
 
-        if y % 1000 == 0 {
-            println!("row {}, {}% finished", y, y as f32 * 100.0 / range as f32);
-        }
-    }
+    let beacon = geometry::find_uncovered(&areas, Vec2D::new(0, 0), bounds)
+        .expect("Every position is covered by a sensor");
 
-    0
+    beacon.x as i64 * 4000000 + beacon.y as i64
 }
 
-fn read_input(filename: &str) -> Vec<Sensor> {
+fn parse(string: String) -> Vec<Sensor> {
     //Sensor at x=2, y=18: closest beacon is at x=-2, y=15
-    let string = std::fs::read_to_string(filename).expect("File not found");
     string
         .lines()
         .map(|l| {
@@ -75,7 +59,7 @@ fn read_input(filename: &str) -> Vec<Sensor> {
                 let sensor = sensor.trim_start_matches("Sensor at ");
                 let beacon = beacon.trim_start_matches("closest beacon is at ");
 
-                Sensor::new(Point::from_string(sensor), Point::from_string(beacon))
+                Sensor::new(parse_point(sensor), parse_point(beacon))
             } else {
                 panic!("Input invalid")
             }
@@ -83,83 +67,57 @@ fn read_input(filename: &str) -> Vec<Sensor> {
         .collect()
 }
 
+fn parse_point(string: &str) -> Vec2D {
+    if let [x, y] = string.split(", ").collect::<Vec<&str>>()[..] {
+        let x = x.trim_start_matches("x=");
+        let y = y.trim_start_matches("y=");
+
+        Vec2D::new(x.parse().unwrap(), y.parse().unwrap())
+    } else {
+        panic!("Input invalid")
+    }
+}
+
 struct Sensor {
-    position: Point,
-    closest_beacon: Point,
-    distance: i32,
+    area: Diamond,
+    closest_beacon: Vec2D,
 }
 
 impl Display for Sensor {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} -> {}", self.position, self.closest_beacon)
+        write!(f, "{} -> {}", self.area.centre, self.closest_beacon)
     }
 }
 
 impl Sensor {
-    fn new(position: Point, closest_beacon: Point) -> Self {
-        let distance = position.manhattan_distance(&closest_beacon);
+    fn new(position: Vec2D, closest_beacon: Vec2D) -> Self {
         Self {
-            position,
+            area: Diamond::through(position, closest_beacon),
             closest_beacon,
-            distance,
         }
     }
 }
 
-struct Point {
-    x: i32,
-    y: i32,
-}
-
-impl Display for Point {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {})", self.x, self.y)
-    }
-}
-
-impl Point {
-    fn new(x: i32, y: i32) -> Self {
-        Point { x, y }
-    }
-
-    fn from_string(string: &str) -> Self {
-        if let [x, y] = string.split(", ").collect::<Vec<&str>>()[..] {
-            let x = x.trim_start_matches("x=");
-            let y = y.trim_start_matches("y=");
-
-            Self {
-                x: x.parse().unwrap(),
-                y: y.parse().unwrap(),
-            }
-        } else {
-            panic!("Input invalid")
-        }
-    }
-
-    fn manhattan_distance(&self, rhs: &Self) -> i32 {
-        (self.x - rhs.x).abs() + (self.y - rhs.y).abs()
-    }
-}
-
-impl PartialEq for Point {
-    fn eq(&self, other: &Self) -> bool {
-        self.x == other.x && self.y == other.y
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_helper::runner::ProcessWithParamsAndAssert;
+
+    fn test_params() -> Params {
+        Params { row: 10, range: 20 }
+    }
 
     #[test]
     fn part_1_works() {
-        let pairs = read_input("test.txt");
-        assert_eq!(part_1(&pairs, 10), 26);
+        Runner::from_test_file(parse)
+            .with_params(test_params())
+            .process_with_params_and_assert(part_1, 26)
     }
 
     #[test]
     fn part_2_works() {
-        let pairs = read_input("test.txt");
-        assert_eq!(part_2(&pairs, 20), 56000011);
+        Runner::from_test_file(parse)
+            .with_params(test_params())
+            .process_with_params_and_assert(part_2, 56000011)
     }
 }
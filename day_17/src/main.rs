@@ -1,4 +1,4 @@
-use std::{collections::HashMap, vec};
+use std::collections::HashMap;
 
 fn main() {
     let input = read_input("input.txt");
@@ -10,6 +10,7 @@ fn main() {
 fn read_input(filename: &str) -> Vec<Direction> {
     let string = std::fs::read_to_string(filename).expect("File not found");
     string
+        .trim()
         .chars()
         .map(|c| match c {
             '<' => Direction::Left,
@@ -19,235 +20,158 @@ fn read_input(filename: &str) -> Vec<Direction> {
         .collect()
 }
 
-fn part_1(directions: &Vec<Direction>) -> i64 {
-    calculate(directions, 2022)
+fn part_1(directions: &[Direction]) -> i64 {
+    tower_height(directions, 2022)
 }
 
-fn part_2(directions: &Vec<Direction>) -> i64 {
-    calculate(directions, 1000000000000i64)
+fn part_2(directions: &[Direction]) -> i64 {
+    tower_height(directions, 1000000000000i64)
 }
 
+/// Every row of the chamber is a byte, the leftmost column being bit 6
+const FULL_ROW: u8 = 0b111_1111;
+const LEFT_WALL: u8 = 0b100_0000;
+const RIGHT_WALL: u8 = 0b000_0001;
+
+/// The rocks from bottom to top, already 2 units away from the left wall
+const ROCKS: [&[u8]; 5] = [
+    &[0b001_1110],
+    &[0b000_1000, 0b001_1100, 0b000_1000],
+    &[0b001_1100, 0b000_0100, 0b000_0100],
+    &[0b001_0000, 0b001_0000, 0b001_0000, 0b001_0000],
+    &[0b001_1000, 0b001_1000],
+];
+
+/// Drops rocks until the chamber is in a state it has been in before
+/// From there on every cycle adds the same height, so those cycles can be skipped
+fn tower_height(directions: &[Direction], total_rocks: i64) -> i64 {
+    let mut chamber = Chamber::new(directions);
+    let mut seen = HashMap::new();
+    let mut skipped_height = 0;
+    let mut cycle_found = false;
+    let mut dropped = 0;
+
+    while dropped < total_rocks {
+        chamber.drop_rock();
+        dropped += 1;
+
+        if cycle_found {
+            continue;
+        }
 
-/// So a lot of experimentation had to happen to get this to work
-/// My assumption of what is wrong is that the first detected cycle somehow starts wrong
-/// This would probably be solved by checking the board state 
-fn calculate(directions: &Vec<Direction>, mut total_blocks: i64) -> i64 {
-    let max_blocks_to_try: i64 = (directions.len() * 5).try_into().unwrap();
-    let simulation = simulate(&directions, max_blocks_to_try, true);
-
-    println!(
-        "Simulation 1: height: {}, blocks: {}, 'start height': {}, 'start blocks': {}",
-        simulation.0, simulation.1, simulation.2 .0, simulation.2 .1
-    );
-
-    // these offsets are added after experimentation
-    let start_blocks = simulation.2 .1 + 5;
-    let start_height = simulation.2 .0 + 2;
-
-    let blocks_per_cycle = simulation.1 - simulation.2 .1 - 5;
-    let height_per_cycle = simulation.0 - simulation.2 .0 - 4;
-
-    let test1 = simulate(&directions, start_blocks + 1 * blocks_per_cycle, false);
-    let _test2 = simulate(&directions, start_blocks + 2 * blocks_per_cycle, false);
-    let test3 = simulate(&directions, start_blocks + 3 * blocks_per_cycle, false);
-    let test4 = simulate(&directions, start_blocks + 4 * blocks_per_cycle, false);
-    let test5 = simulate(&directions, start_blocks + 5 * blocks_per_cycle, false);
-    let test6 = simulate(&directions, start_blocks + 20 * blocks_per_cycle, false);
-
-    assert_eq!(test5.0 - test4.0, test4.0 - test3.0);
-    assert_eq!(test4.0 - start_height, 4 * height_per_cycle);
-    assert_eq!(test5.0 - start_height, 5 * height_per_cycle);
-    assert_eq!(test6.0 - start_height, 20 * height_per_cycle);
-
-    total_blocks -= start_blocks;
-
-    let known_cycle_count = total_blocks / blocks_per_cycle;
-
-    println!("known cycles: {}", known_cycle_count);
-
-    let unknown_blocks = total_blocks % blocks_per_cycle;
-
-    println!("unknown blocks: {}", unknown_blocks);
-
-    let simulation_3 = simulate(
-        &directions,
-        start_blocks + blocks_per_cycle + unknown_blocks,
-        false,
-    );
-
-    println!("height of second run: {}", simulation_3.0);
-
-    let unknown_height = simulation_3.0 - test1.0;
-
-    let test7 = simulate(&directions, start_blocks + 20 * blocks_per_cycle + unknown_blocks, false);
-
-    assert_eq!(test7.0, start_height + 20 * height_per_cycle + unknown_height);
+        let key = (chamber.rock_index, chamber.jet_index, chamber.surface());
+        if let Some((previous_dropped, previous_height)) =
+            seen.insert(key, (dropped, chamber.height()))
+        {
+            let cycle_length = dropped - previous_dropped;
+            let cycles = (total_rocks - dropped) / cycle_length;
 
-    println!("height of unknown blocks: {}", unknown_height);
+            skipped_height = cycles * (chamber.height() - previous_height);
+            dropped += cycles * cycle_length;
+            cycle_found = true;
+        }
+    }
 
-    start_height + known_cycle_count * height_per_cycle + unknown_height
+    chamber.height() + skipped_height
 }
 
-fn simulate(
-    directions: &Vec<Direction>,
-    block_count: i64,
-    break_on_cycle: bool,
-) -> (i64, i64, (i64, i64)) {
-    // create a field with worst-case height (only vertical bars stacked, with 3 extra for the start)
-    let mut field = vec![vec![false; 7]; (4 * block_count + 3).try_into().unwrap()];
-
-    let blocks = vec![
-        BlockTypes::Horizontal,
-        BlockTypes::Plus,
-        BlockTypes::L,
-        BlockTypes::Vertical,
-        BlockTypes::Square,
-    ];
-
-    let mut blocks_iter = blocks.iter().enumerate().cycle();
-    let mut directions_iter = directions.iter().enumerate().cycle();
-    let mut seen_combinations = vec![];
-    let mut height_and_count_at_combination = HashMap::new();
-    let mut current_highest = 0;
+struct Chamber<'a> {
+    rows: Vec<u8>,
+    jets: &'a [Direction],
+    rock_index: usize,
+    jet_index: usize,
+}
 
-    for _index in 0..block_count {
-        let (block_index, block_type) = blocks_iter.next().unwrap();
+impl<'a> Chamber<'a> {
+    fn new(jets: &'a [Direction]) -> Self {
+        Self {
+            rows: vec![],
+            jets,
+            rock_index: 0,
+            jet_index: 0,
+        }
+    }
 
-        let start_y = current_highest + 3;
-        let start_x = 2;
+    fn height(&self) -> i64 {
+        self.rows.len() as i64
+    }
 
-        let mut current_position = (start_x, start_y);
+    fn drop_rock(&mut self) {
+        let mut rock = ROCKS[self.rock_index].to_vec();
+        self.rock_index = (self.rock_index + 1) % ROCKS.len();
 
-        let offsets = get_block_offsets(block_type);
+        let mut y = self.rows.len() + 3;
 
         loop {
-            // blow in the wind
-            let (direction_index, direction) = directions_iter.next().unwrap();
-
-            let direction_offset;
-            match direction {
-                Direction::Left => direction_offset = (-1, 0),
-                Direction::Right => direction_offset = (1, 0),
-            }
-            try_move(&offsets, &mut current_position, direction_offset, &field);
-
-            // fall
-            let direction_offset = (0, -1);
-            if !try_move(&offsets, &mut current_position, direction_offset, &field) {
-                let positions = calculate_positions(&offsets, &current_position, (0, 0));
-                let mut local_highest = 0;
-
-                for position in positions {
-                    let y: usize = position.1.try_into().unwrap();
-                    let x: usize = position.0.try_into().unwrap();
-
-                    if y as i64 + 1 > local_highest {
-                        local_highest = y as i64 + 1;
-                    }
-
-                    if y as i64 + 1 > current_highest {
-                        current_highest = y as i64 + 1;
-                    }
+            let jet = &self.jets[self.jet_index];
+            self.jet_index = (self.jet_index + 1) % self.jets.len();
 
-                    field[y][x] = true;
+            if let Some(pushed) = push(&rock, jet) {
+                if !self.collides(&pushed, y) {
+                    rock = pushed;
                 }
+            }
 
-                let key = (block_index, direction_index);
-
-                if seen_combinations.contains(&key) && break_on_cycle {
-                    println!("cycle found at ({}, {}):", block_index, direction_index);
-                    _print(&current_highest, &field);
-                    return (local_highest, _index, height_and_count_at_combination[&key]);
-                }
-                height_and_count_at_combination.insert(key, (local_highest, _index));
-                seen_combinations.push(key);
-
+            if y == 0 || self.collides(&rock, y - 1) {
                 break;
             }
+            y -= 1;
         }
-    }
-
-    println!("all blocks simulated:");
-    _print(&current_highest, &field);
-    (current_highest, 0, (0, 0))
-}
 
-fn _print(current_highest: &i64, field: &Vec<Vec<bool>>) {
-    //print field
-    let skip = (current_highest - 10).max(0);
-    for row in field.iter().skip(skip.try_into().unwrap()).take(13).rev() {
-        print!("|");
-        for field in row {
-            if *field {
-                print!("#")
-            } else {
-                print!(".")
+        for (i, row) in rock.iter().enumerate() {
+            if y + i >= self.rows.len() {
+                self.rows.push(0);
             }
+            self.rows[y + i] |= row;
         }
-        println!("|")
     }
-    //stdin().read_line(&mut "".to_owned());
-}
 
-fn try_move(
-    offsets: &Vec<(i64, i64)>,
-    current_position: &mut (i64, i64),
-    direction_offset: (i64, i64),
-    field: &Vec<Vec<bool>>,
-) -> bool {
-    let new_positions = calculate_positions(offsets, &*current_position, direction_offset);
-    if check_if_free(field, &new_positions) {
-        *current_position = (
-            current_position.0 + direction_offset.0,
-            current_position.1 + direction_offset.1,
-        );
-        return true;
+    fn collides(&self, rock: &[u8], y: usize) -> bool {
+        rock.iter()
+            .enumerate()
+            .any(|(i, row)| self.rows.get(y + i).is_some_and(|r| r & row != 0))
     }
 
-    false
-}
+    /// The cells a falling rock can still reach, row by row from the top
+    /// Everything below can never be touched again, so this is all that matters for the future
+    fn surface(&self) -> Vec<u8> {
+        let mut surface = vec![];
+        let mut reachable = FULL_ROW;
 
-fn calculate_positions(
-    offsets: &Vec<(i64, i64)>,
-    current_position: &(i64, i64),
-    direction_offset: (i64, i64),
-) -> Vec<(i64, i64)> {
-    let new_positions: Vec<(i64, i64)> = offsets
-        .iter()
-        .map(|o| {
-            (
-                current_position.0 + o.0 + direction_offset.0,
-                current_position.1 + o.1 + direction_offset.1,
-            )
-        })
-        .collect();
-    new_positions
-}
+        for row in self.rows.iter().rev() {
+            let free = !row & FULL_ROW;
+            let mut next = reachable & free;
 
-fn check_if_free(field: &Vec<Vec<bool>>, positions: &Vec<(i64, i64)>) -> bool {
-    for position in positions {
-        if position.0 < 0 || position.0 >= 7 || position.1 < 0 {
-            return false;
-        }
+            loop {
+                let spread = (next | next << 1 | next >> 1) & free;
+                if spread == next {
+                    break;
+                }
+                next = spread;
+            }
 
-        let y: usize = position.1.try_into().unwrap();
-        let x: usize = position.0.try_into().unwrap();
+            if next == 0 {
+                break;
+            }
 
-        if field[y][x] {
-            return false;
+            surface.push(next);
+            reachable = next;
         }
-    }
 
-    true
+        surface
+    }
 }
 
-fn get_block_offsets(block_type: &BlockTypes) -> Vec<(i64, i64)> {
-    match block_type {
-        BlockTypes::Horizontal => vec![(0, 0), (1, 0), (2, 0), (3, 0)],
-        BlockTypes::Plus => vec![(0, 1), (1, 1), (2, 1), (1, 2), (1, 0)],
-        BlockTypes::L => vec![(0, 0), (1, 0), (2, 0), (2, 1), (2, 2)],
-        BlockTypes::Vertical => vec![(0, 0), (0, 1), (0, 2), (0, 3)],
-        BlockTypes::Square => vec![(0, 0), (1, 0), (0, 1), (1, 1)],
+/// The rock moved one unit sideways, or None when a wall is in the way
+fn push(rock: &[u8], direction: &Direction) -> Option<Vec<u8>> {
+    match direction {
+        Direction::Left if rock.iter().all(|r| r & LEFT_WALL == 0) => {
+            Some(rock.iter().map(|r| r << 1).collect())
+        }
+        Direction::Right if rock.iter().all(|r| r & RIGHT_WALL == 0) => {
+            Some(rock.iter().map(|r| r >> 1).collect())
+        }
+        _ => None,
     }
 }
 
@@ -257,14 +181,6 @@ enum Direction {
     Right,
 }
 
-enum BlockTypes {
-    Horizontal,
-    Plus,
-    L,
-    Vertical,
-    Square,
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -280,4 +196,17 @@ mod tests {
         let nodes = read_input("test.txt");
         assert_eq!(part_2(&nodes), 1514285714288);
     }
+
+    #[test]
+    fn skipping_cycles_matches_dropping_every_rock() {
+        let input = read_input("test.txt");
+        let mut chamber = Chamber::new(&input);
+
+        for rocks in 1..=5000 {
+            chamber.drop_rock();
+            if rocks % 997 == 0 {
+                assert_eq!(tower_height(&input, rocks), chamber.height());
+            }
+        }
+    }
 }
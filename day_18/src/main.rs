@@ -49,7 +49,7 @@ fn part_2(cubes: &HashMap<Point, Cube>) -> i32 {
         for z in 0..=depth {
             flood_fill(
                 &mut space,
-                (0 as i32, y as i32, z as i32),
+                (0, y as i32, z as i32),
                 width,
                 height,
                 depth,
@@ -68,7 +68,7 @@ fn part_2(cubes: &HashMap<Point, Cube>) -> i32 {
         for z in 0..=depth {
             flood_fill(
                 &mut space,
-                (x as i32, 0 as i32, z as i32),
+                (x as i32, 0, z as i32),
                 width,
                 height,
                 depth,
@@ -87,7 +87,7 @@ fn part_2(cubes: &HashMap<Point, Cube>) -> i32 {
         for y in 0..=height {
             flood_fill(
                 &mut space,
-                (x as i32, y as i32, 0 as i32),
+                (x as i32, y as i32, 0),
                 width,
                 height,
                 depth,
@@ -149,7 +149,7 @@ fn part_2(cubes: &HashMap<Point, Cube>) -> i32 {
 }
 
 fn flood_fill(
-    space: &mut Vec<Vec<Vec<Type>>>,
+    space: &mut [Vec<Vec<Type>>],
     start: Point,
     width: usize,
     height: usize,
@@ -216,12 +216,12 @@ fn get_directions() -> Vec<(i32, i32, i32)> {
 }
 
 fn get_bounds(cubes: &HashMap<(i32, i32, i32), Cube>) -> (i32, i32, i32, i32, i32, i32) {
-    let mut min_x = i32::max_value();
-    let mut max_x = i32::min_value();
-    let mut min_y = i32::max_value();
-    let mut max_y = i32::min_value();
-    let mut min_z = i32::max_value();
-    let mut max_z = i32::min_value();
+    let mut min_x = i32::MAX;
+    let mut max_x = i32::MIN;
+    let mut min_y = i32::MAX;
+    let mut max_y = i32::MIN;
+    let mut min_z = i32::MAX;
+    let mut max_z = i32::MIN;
 
     for cube in cubes.values() {
         if cube.x < min_x {
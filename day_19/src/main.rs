@@ -1,468 +1,280 @@
-use std::fmt::Display;
+use aoc_helper::{
+    progress,
+    runner::{ProcessWithTimeoutAndWrite, Runner},
+};
+use std::{
+    collections::HashSet,
+    sync::atomic::{AtomicU64, Ordering},
+};
 
 fn main() {
-    let input = read_input("input.txt");
-
-    println!("Part 1: {}", part_1(&input));
-    println!("Part 2: {}", part_2(&input));
+    let runner = Runner::from_input_file(parse);
+    runner.process_with_timeout_and_write_part_1(part_1);
+    runner.process_with_timeout_and_write_part_2(part_2);
 }
 
-fn read_input(filename: &str) -> Vec<Blueprint> {
-    std::fs::read_to_string(filename)
-        .expect("File not found")
-        .lines()
-        .map(Blueprint::from_string)
-        .collect()
+fn parse(string: String) -> Vec<Blueprint> {
+    string.lines().map(Blueprint::from_string).collect()
 }
 
 fn part_1(blueprints: &[Blueprint]) -> u32 {
-    blueprints
+    solve_all(blueprints, 24)
         .iter()
-        .map(|b| calculate_max_geodes(b, 24) * b.id)
+        .zip(blueprints)
+        .map(|(geodes, blueprint)| geodes * blueprint.id)
         .sum()
 }
 
 fn part_2(blueprints: &[Blueprint]) -> u32 {
-    blueprints
-        .iter()
-        .take(3)
-        .map(|b| calculate_max_geodes(b, 32))
-        .product()
+    let first = &blueprints[..3.min(blueprints.len())];
+    solve_all(first, 32).iter().product()
 }
 
-fn calculate_max_geodes(blueprint: &Blueprint, turns: u32) -> u32 {
-    println!("Blueprint: {}", blueprint.id);
-
-    let mut queue = vec![];
-
-    let initial_ore = GameState::initial(RobotType::Ore, turns);
-    let initial_clay = GameState::initial(RobotType::Clay, turns);
+/// Every blueprint is searched on its own thread, the progress counts the finished blueprints
+fn solve_all(blueprints: &[Blueprint], turns: u32) -> Vec<u32> {
+    let progress = progress::current();
+    progress.set_total(blueprints.len() as u64);
+    let finished = AtomicU64::new(0);
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = blueprints
+            .iter()
+            .map(|blueprint| {
+                let (progress, finished) = (&progress, &finished);
+                scope.spawn(move || {
+                    let geodes = blueprint.max_geodes(turns);
+                    progress.update(finished.fetch_add(1, Ordering::Relaxed) + 1);
+                    geodes
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("Search thread panicked"))
+            .collect()
+    })
+}
 
-    queue.push(initial_ore);
-    queue.push(initial_clay);
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct GameState {
+    time_remaining: u32,
+    robots: Vec<u32>,
+    resources: Vec<u32>,
+}
 
-    let mut max_geodes = 0;
+struct Blueprint {
+    id: u32,
+    /// The amount of each resource a robot costs, indexed by robot then resource
+    /// Resources are numbered in the order they appear, a robot collects the resource it is named after
+    costs: Vec<Vec<u32>>,
+    /// Only one robot can be built per turn, so more robots than the highest cost is useless
+    robot_caps: Vec<u32>,
+    /// The resource to collect as much of as possible, the last robot in the blueprint
+    goal: usize,
+}
 
-    loop {
-        let Some(mut current_state) = queue.pop()
-        else{
-            break max_geodes;
+impl Blueprint {
+    //Blueprint 1: Each ore robot costs 3 ore. Each clay robot costs 3 ore. Each obsidian robot costs 3 ore and 16 clay. Each geode robot costs 3 ore and 9 obsidian.
+    fn from_string(string: &str) -> Self {
+        let [header, robots] = string.split(": ").collect::<Vec<&str>>()[..] else {
+            panic!("Invalid blueprint: {}", string)
         };
-
-        match current_state.saving_for {
-            RobotType::Ore => {
-                if blueprint.ore_robot_cost_ore <= current_state.ore {
-                    current_state.take_turn(Some(RobotType::Ore), blueprint);
-
-                    if !could_in_theory_get_enough_geodes(&current_state, &max_geodes) {
-                        continue;
-                    }
-
-                    if !finish_turn_if_last(&current_state, &mut max_geodes) {
-                        add_next_steps(&mut current_state, &mut queue, blueprint, &mut max_geodes);
-                    }
-
-                    continue;
-                }
-            }
-            RobotType::Clay => {
-                if blueprint.clay_robot_cost_ore <= current_state.ore {
-                    current_state.take_turn(Some(RobotType::Clay), blueprint);
-
-                    if !could_in_theory_get_enough_geodes(&current_state, &max_geodes) {
-                        continue;
-                    }
-
-                    if !finish_turn_if_last(&current_state, &mut max_geodes) {
-                        add_next_steps(&mut current_state, &mut queue, blueprint, &mut max_geodes);
-                    }
-
-                    continue;
-                }
-            }
-            RobotType::Obsidian => {
-                if blueprint.obsidian_robot_cost_ore <= current_state.ore
-                    && blueprint.obsidian_robot_cost_clay <= current_state.clay
-                {
-                    current_state.take_turn(Some(RobotType::Obsidian), blueprint);
-
-                    if !could_in_theory_get_enough_geodes(&current_state, &max_geodes) {
-                        continue;
-                    }
-
-                    if !finish_turn_if_last(&current_state, &mut max_geodes) {
-                        add_next_steps(&mut current_state, &mut queue, blueprint, &mut max_geodes);
-                    }
-
-                    continue;
-                }
-            }
-            RobotType::Geode => {
-                if blueprint.geode_robot_cost_ore <= current_state.ore
-                    && blueprint.geode_robot_cost_obsidian <= current_state.obsidian
-                {
-                    current_state.take_turn(Some(RobotType::Geode), blueprint);
-
-                    if !could_in_theory_get_enough_geodes(&current_state, &max_geodes) {
-                        continue;
-                    }
-
-                    if !finish_turn_if_last(&current_state, &mut max_geodes) {
-                        add_next_steps(&mut current_state, &mut queue, blueprint, &mut max_geodes);
-                    }
-
-                    continue;
-                }
+        let id = header
+            .trim_start_matches("Blueprint ")
+            .parse()
+            .expect("Invalid blueprint id");
+
+        let mut resources: Vec<String> = vec![];
+        let mut index_of = |name: &str| match resources.iter().position(|r| r == name) {
+            Some(index) => index,
+            None => {
+                resources.push(name.to_owned());
+                resources.len() - 1
             }
-        }
-
-        current_state.take_turn(None, blueprint);
+        };
 
-        if !could_in_theory_get_enough_geodes(&current_state, &max_geodes) {
-            continue;
+        let mut robot_costs = vec![];
+        for robot in robots.split('.').map(str::trim).filter(|s| !s.is_empty()) {
+            let robot = robot.trim_start_matches("Each ");
+            let [name, costs] = robot.split(" robot costs ").collect::<Vec<&str>>()[..] else {
+                panic!("Invalid robot: {}", robot)
+            };
+
+            let robot_index = index_of(name);
+            let costs: Vec<(usize, u32)> = costs
+                .split(" and ")
+                .map(|cost| {
+                    let [amount, resource] = cost.split(' ').collect::<Vec<&str>>()[..] else {
+                        panic!("Invalid cost: {}", cost)
+                    };
+                    (index_of(resource), amount.parse().expect("Invalid amount"))
+                })
+                .collect();
+            robot_costs.push((robot_index, costs));
         }
 
-        if !finish_turn_if_last(&current_state, &mut max_geodes) {
-            queue.push(current_state);
+        let mut costs = vec![vec![0; resources.len()]; resources.len()];
+        for (robot, robot_cost) in &robot_costs {
+            for (resource, amount) in robot_cost {
+                costs[*robot][*resource] = *amount;
+            }
         }
-    }
-}
 
-/// Very naively calculates how many geodes a state could create
-/// Important to note is this method never underestimates
-fn could_in_theory_get_enough_geodes(current_state: &GameState, max_geodes: &u32) -> bool {
-    let mut robot_count = current_state.geode_robot_count;
-    let mut geode_count = current_state.geodes;
+        // a resource without a robot in the blueprint gets a cap of 0, so it is never built
+        let goal = robot_costs.last().expect("Blueprint without robots").0;
+        let robot_caps = (0..resources.len())
+            .map(|resource| match resource {
+                _ if !robot_costs.iter().any(|(robot, _)| *robot == resource) => 0,
+                _ if resource == goal => u32::MAX,
+                _ => costs.iter().map(|c| c[resource]).max().unwrap_or(0),
+            })
+            .collect();
 
-    for _ in 0..current_state.time_remaining {
-        geode_count += robot_count;
-        robot_count += 1;
-    }
-
-    geode_count > *max_geodes
-}
-
-fn finish_turn_if_last(current_state: &GameState, max_geodes: &mut u32) -> bool {
-    if current_state.time_remaining == 0 {
-        if current_state.geodes > *max_geodes {
-            *max_geodes = current_state.geodes;
-            println!("Max: {}", max_geodes);
-        }
-        return true;
-    }
-    false
-}
-
-fn add_next_steps(
-    current_state: &mut GameState,
-    queue: &mut Vec<GameState>,
-    blueprint: &Blueprint,
-    max_geodes: &mut u32,
-) {
-    let mut pushed_value = false;
-
-    pushed_value |= try_add_ore_robot(current_state, blueprint, queue);
-    pushed_value |= try_add_clay_robot(current_state, blueprint, queue);
-    pushed_value |= try_add_obsidian_robot(current_state, blueprint, queue);
-    pushed_value |= try_add_geode_robot(current_state, blueprint, queue);
-
-    if !pushed_value {
-        while current_state.time_remaining > 0 {
-            current_state.take_turn(None, blueprint)
+        Self {
+            id,
+            costs,
+            robot_caps,
+            goal,
         }
-        finish_turn_if_last(current_state, max_geodes);
-    }
-}
-
-fn try_add_ore_robot(
-    current_state: &mut GameState,
-    blueprint: &Blueprint,
-    queue: &mut Vec<GameState>,
-) -> bool {
-    if current_state.ore + current_state.time_remaining * current_state.ore_robot_count
-        >= blueprint.ore_robot_cost_ore
-    {
-        queue.push(current_state.clone_with_robot_to_build(RobotType::Ore));
-        return true;
-    }
-
-    false
-}
-
-fn try_add_clay_robot(
-    current_state: &mut GameState,
-    blueprint: &Blueprint,
-    queue: &mut Vec<GameState>,
-) -> bool {
-    if current_state.ore + current_state.time_remaining * current_state.ore_robot_count
-        >= blueprint.clay_robot_cost_ore
-    {
-        queue.push(current_state.clone_with_robot_to_build(RobotType::Clay));
-        return true;
     }
 
-    false
-}
-
-fn try_add_obsidian_robot(
-    current_state: &mut GameState,
-    blueprint: &Blueprint,
-    queue: &mut Vec<GameState>,
-) -> bool {
-    if current_state.ore + current_state.time_remaining * current_state.ore_robot_count
-        >= blueprint.obsidian_robot_cost_ore
-        && current_state.clay + current_state.time_remaining * current_state.clay_robot_count
-            >= blueprint.obsidian_robot_cost_clay
-        && current_state.clay_robot_count > 0
-    {
-        queue.push(current_state.clone_with_robot_to_build(RobotType::Obsidian));
-        return true;
-    }
+    fn max_geodes(&self, turns: u32) -> u32 {
+        let mut robots = vec![0; self.costs.len()];
+        robots[0] = 1;
 
-    false
-}
+        let state = GameState {
+            time_remaining: turns,
+            robots,
+            resources: vec![0; self.costs.len()],
+        };
 
-fn try_add_geode_robot(
-    current_state: &mut GameState,
-    blueprint: &Blueprint,
-    queue: &mut Vec<GameState>,
-) -> bool {
-    if current_state.ore + current_state.time_remaining * current_state.ore_robot_count
-        >= blueprint.geode_robot_cost_ore
-        && current_state.obsidian
-            + current_state.time_remaining * current_state.obsidian_robot_count
-            >= blueprint.geode_robot_cost_obsidian
-        && current_state.clay_robot_count > 0
-    {
-        queue.push(current_state.clone_with_robot_to_build(RobotType::Geode));
-        return true;
+        let mut best = 0;
+        self.search(state, &mut best, &mut HashSet::new());
+        best
     }
 
-    false
-}
-
-#[derive(Clone)]
-struct GameState {
-    time_remaining: u32,
-    saving_for: RobotType,
-    ore_robot_count: u32,
-    clay_robot_count: u32,
-    obsidian_robot_count: u32,
-    geode_robot_count: u32,
-    ore: u32,
-    clay: u32,
-    obsidian: u32,
-    geodes: u32,
-}
+    /// Depth first, each step picks the next robot to build and waits until it can be afforded
+    fn search(&self, state: GameState, best: &mut u32, seen: &mut HashSet<GameState>) {
+        let idle = state.resources[self.goal] + state.robots[self.goal] * state.time_remaining;
+        *best = (*best).max(idle);
 
-impl GameState {
-    fn initial(saving_for: RobotType, turns: u32) -> Self {
-        Self {
-            time_remaining: turns,
-            saving_for,
-            ore_robot_count: 1,
-            clay_robot_count: 0,
-            obsidian_robot_count: 0,
-            geode_robot_count: 0,
-            ore: 0,
-            clay: 0,
-            obsidian: 0,
-            geodes: 0,
+        if self.upper_bound(&state) <= *best || !seen.insert(self.normalise(&state)) {
+            return;
         }
-    }
 
-    fn take_turn(&mut self, robot_to_build: Option<RobotType>, blueprint: &Blueprint) {
-        self.ore += self.ore_robot_count;
-        self.clay += self.clay_robot_count;
-        self.obsidian += self.obsidian_robot_count;
-        self.geodes += self.geode_robot_count;
-
-        if let Some(new_type) = robot_to_build {
-            match new_type {
-                RobotType::Ore => {
-                    self.ore_robot_count += 1;
-                    self.ore -= blueprint.ore_robot_cost_ore
-                }
-                RobotType::Clay => {
-                    self.clay_robot_count += 1;
-                    self.ore -= blueprint.clay_robot_cost_ore;
-                }
-                RobotType::Obsidian => {
-                    self.obsidian_robot_count += 1;
-                    self.ore -= blueprint.obsidian_robot_cost_ore;
-                    self.clay -= blueprint.obsidian_robot_cost_clay;
-                }
-                RobotType::Geode => {
-                    self.geode_robot_count += 1;
-                    self.ore -= blueprint.geode_robot_cost_ore;
-                    self.obsidian -= blueprint.geode_robot_cost_obsidian;
-                }
+        // the goal robot first, it finds good answers early so the bound prunes more
+        for robot in (0..self.costs.len()).rev() {
+            if state.robots[robot] >= self.robot_caps[robot] {
+                continue;
             }
-        }
 
-        self.time_remaining -= 1;
-    }
+            let Some(wait) = self.turns_until_affordable(&state, robot) else {
+                continue;
+            };
+            if wait + 1 >= state.time_remaining {
+                continue;
+            }
 
-    fn clone_with_robot_to_build(&self, robot_to_build: RobotType) -> Self{
-        let mut clone = self.clone();
-        clone.saving_for = robot_to_build;
+            let mut next = state.clone();
+            next.time_remaining -= wait + 1;
+            for resource in 0..self.costs.len() {
+                next.resources[resource] = state.resources[resource]
+                    + state.robots[resource] * (wait + 1)
+                    - self.costs[robot][resource];
+            }
+            next.robots[robot] += 1;
 
-        clone
+            self.search(next, best, seen);
+        }
     }
-}
 
-impl PartialEq for GameState {
-    fn eq(&self, other: &Self) -> bool {
-        self.time_remaining == other.time_remaining
-            && self.ore_robot_count == other.ore_robot_count
-            && self.clay_robot_count == other.clay_robot_count
-            && self.obsidian_robot_count == other.obsidian_robot_count
-            && self.geode_robot_count == other.geode_robot_count
-            && self.ore == other.ore
-            && self.clay == other.clay
-            && self.obsidian == other.obsidian
-            && self.geodes == other.geodes
+    fn turns_until_affordable(&self, state: &GameState, robot: usize) -> Option<u32> {
+        self.costs[robot]
+            .iter()
+            .enumerate()
+            .map(|(resource, cost)| {
+                let missing = cost.saturating_sub(state.resources[resource]);
+                match (missing, state.robots[resource]) {
+                    (0, _) => Some(0),
+                    (_, 0) => None,
+                    (missing, robots) => Some(missing.div_ceil(robots)),
+                }
+            })
+            .try_fold(0, |wait, turns| Some(wait.max(turns?)))
     }
-}
 
-impl PartialOrd for GameState {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        if self == other {
-            return Some(std::cmp::Ordering::Equal);
-        }
+    /// Lets every robot type save up in its own stock, so robots of different types never
+    /// compete for resources and can all be built in the same turn
+    /// This never underestimates, since it builds every type at least as early as possible
+    fn upper_bound(&self, state: &GameState) -> u32 {
+        let mut robots = state.robots.clone();
+        let mut stocks = vec![state.resources.clone(); self.costs.len()];
+        let mut goal = state.resources[self.goal];
+
+        for _ in 0..state.time_remaining {
+            let built: Vec<bool> = stocks
+                .iter_mut()
+                .zip(&self.costs)
+                .map(|(stock, cost)| {
+                    let affordable = stock.iter().zip(cost).all(|(s, c)| s >= c);
+                    if affordable {
+                        stock.iter_mut().zip(cost).for_each(|(s, c)| *s -= c);
+                    }
+                    affordable
+                })
+                .collect();
 
-        if self.ore >= other.ore
-            && self.clay >= other.clay
-            && self.obsidian >= other.obsidian
-            && self.geodes >= other.geodes
-            && self.ore_robot_count >= other.ore_robot_count
-            && self.clay_robot_count >= other.clay_robot_count
-            && self.obsidian_robot_count >= other.obsidian_robot_count
-            && self.geode_robot_count >= other.geode_robot_count
-            && self.time_remaining >= other.time_remaining
-        {
-            return Some(std::cmp::Ordering::Greater);
-        }
+            for stock in stocks.iter_mut() {
+                stock.iter_mut().zip(&robots).for_each(|(s, r)| *s += r);
+            }
+            goal += robots[self.goal];
 
-        if self.ore <= other.ore
-            && self.clay <= other.clay
-            && self.obsidian <= other.obsidian
-            && self.geodes <= other.geodes
-            && self.ore_robot_count <= other.ore_robot_count
-            && self.clay_robot_count <= other.clay_robot_count
-            && self.obsidian_robot_count <= other.obsidian_robot_count
-            && self.geode_robot_count <= other.geode_robot_count
-            && self.time_remaining <= other.time_remaining
-        {
-            return Some(std::cmp::Ordering::Less);
+            for (robot, built) in built.into_iter().enumerate() {
+                robots[robot] += built as u32;
+            }
         }
 
-        None
-    }
-}
-
-impl Display for GameState {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(
-            f,
-            "ore robots:      {}, ore:      {}",
-            self.ore_robot_count, self.ore
-        )
-        .and(writeln!(
-            f,
-            "clay robots:     {}, clay:     {}",
-            self.clay_robot_count, self.clay
-        ))
-        .and(writeln!(
-            f,
-            "obsidian robots: {}, obsidian: {}",
-            self.obsidian_robot_count, self.obsidian
-        ))
-        .and(writeln!(
-            f,
-            "geode robots:    {}, geodes:   {}",
-            self.geode_robot_count, self.geodes
-        ))
-        .and(writeln!(f, "time remaining:  {}", self.time_remaining))
+        goal
     }
-}
-
-struct Blueprint {
-    //Blueprint 1: Each ore robot costs 3 ore. Each clay robot costs 3 ore. Each obsidian robot costs 3 ore and 16 clay. Each geode robot costs 3 ore and 9 obsidian.
-    id: u32,
-    ore_robot_cost_ore: u32,
-    clay_robot_cost_ore: u32,
-    obsidian_robot_cost_ore: u32,
-    obsidian_robot_cost_clay: u32,
-    geode_robot_cost_ore: u32,
-    geode_robot_cost_obsidian: u32,
-}
-
-impl Blueprint {
-    fn from_string(string: &str) -> Self {
-        let parts = string.split(". ");
-
-        let [part_1_and_2, part3, part4, part5] = parts.collect::<Vec<&str>>()[..]
-        else{
-            panic!("cannot parse parts")
-        };
 
-        let [part1, part2] = part_1_and_2.split(": ").collect::<Vec<&str>>()[..]
-        else{
-            panic!("cannot parse part 1 and 2")
-        };
-
-        let id = part1.split(' ').nth(1).unwrap().parse().unwrap();
-
-        let ore_robot_cost_ore = parse_cost_one_resource(part2);
-        let clay_robot_cost_ore = parse_cost_one_resource(part3);
-        let (obsidian_robot_cost_ore, obsidian_robot_cost_clay) = parse_cost_two_resources(part4);
-        let (geode_robot_cost_ore, geode_robot_cost_obsidian) = parse_cost_two_resources(part5);
-
-        Self {
-            id,
-            ore_robot_cost_ore,
-            clay_robot_cost_ore,
-            obsidian_robot_cost_ore,
-            obsidian_robot_cost_clay,
-            geode_robot_cost_ore,
-            geode_robot_cost_obsidian,
+    /// Resources beyond what can still be spent make no difference, so they are capped
+    /// to let more states be recognised as seen
+    fn normalise(&self, state: &GameState) -> GameState {
+        let mut state = state.clone();
+        for resource in 0..self.costs.len() {
+            if resource != self.goal {
+                let spendable = self.robot_caps[resource] * state.time_remaining;
+                state.resources[resource] = state.resources[resource].min(spendable);
+            }
         }
+        state
     }
 }
 
-#[derive(Clone, PartialEq)]
-enum RobotType {
-    Ore,
-    Clay,
-    Obsidian,
-    Geode,
-}
-
-fn parse_cost_one_resource(string: &str) -> u32 {
-    string.split(' ').nth(4).unwrap().parse().unwrap()
-}
-
-fn parse_cost_two_resources(string: &str) -> (u32, u32) {
-    (
-        string.split(' ').nth(4).unwrap().parse().unwrap(),
-        string.split(' ').nth(7).unwrap().parse().unwrap(),
-    )
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_helper::runner::ProcessAndAssert;
 
     #[test]
     fn part_1_works() {
-        let input = read_input("test.txt");
-        assert_eq!(part_1(&input), 33);
+        Runner::from_test_file(parse).process_and_assert(part_1, 33)
     }
 
     #[test]
     fn part_2_works() {
-        let nodes = read_input("test.txt");
-        assert_eq!(part_2(&nodes), 3472);
+        Runner::from_test_file(parse).process_and_assert(part_2, 3472)
+    }
+
+    #[test]
+    fn reads_resources_from_the_blueprint() {
+        let blueprint = Blueprint::from_string(
+            "Blueprint 7: Each wood robot costs 2 wood. Each gold robot costs 3 wood.",
+        );
+        assert_eq!(blueprint.id, 7);
+        assert_eq!(blueprint.costs, vec![vec![2, 0], vec![3, 0]]);
+        assert_eq!(blueprint.goal, 1);
+        assert_eq!(blueprint.max_geodes(6), 2);
     }
 }
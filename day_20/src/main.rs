@@ -1,3 +1,5 @@
+use aoc_helper::collections::{IndexedRing, RingHandle};
+
 fn main() {
     let input = read_input("input.txt");
 
@@ -12,69 +14,41 @@ fn read_input(filename: &str) -> Vec<i64> {
 }
 
 fn part_1(input: &[i64]) -> i64 {
-    let mut result = create_result_vec(input);
-    perform_cycle(input, &mut result);
-    calculate_result(result)
+    mix(input, 1)
 }
 
 fn part_2(input: &[i64]) -> i64 {
     let key = 811589153;
     let input: Vec<i64> = input.iter().map(|v| v * key).collect();
 
-    let mut result = create_result_vec(&input);
-
-    for _ in 0..10{
-        perform_cycle(&input, &mut result);
-    }
-
-    calculate_result(result)
+    mix(&input, 10)
 }
 
-///result contains the original indexes and the corresponding values
-fn create_result_vec(input: &[i64]) -> Vec<(usize, i64)> {
-    input
-        .iter()
-        .enumerate()
-        .map(|(key, value)| (key, *value))
-        .collect()
-}
-
-fn perform_cycle(input: &[i64], result: &mut Vec<(usize, i64)>) {
-    let size = (input.len() - 1) as i64;
-
-    for number in input.iter().enumerate() {
-        let current_index = result.iter().position(|i| i.0 == number.0).unwrap();
-        let moving_item = result.remove(current_index);
-        let movement = moving_item.1;
-        let mut new_index = (current_index as i64 + movement) % size;
+/// Every number moves in the order of the input, the handles keep track of that order
+fn mix(input: &[i64], rounds: usize) -> i64 {
+    let mut ring = IndexedRing::new();
+    let handles: Vec<RingHandle> = input.iter().map(|n| ring.push(*n)).collect();
 
-        if new_index == 0 && movement < 0 {
-            result.push(moving_item)
-        } else {
-            if new_index < 0 {
-                new_index += size
-            }
-
-            result.insert(new_index as usize, moving_item);
+    for _ in 0..rounds {
+        for handle in &handles {
+            ring.move_by(*handle, *ring.get(*handle));
         }
     }
-}
-
-fn calculate_result(result: Vec<(usize, i64)>) -> i64 {
-    let start_index = result.iter().position(|v| v.1 == 0).unwrap();
-
-    let res1 = result[(start_index + 1000) % result.len()].1;
-    let res2 = result[(start_index + 2000) % result.len()].1;
-    let res3 = result[(start_index + 3000) % result.len()].1;
 
-    res1 + res2 + res3
+    calculate_result(&ring, &handles)
 }
 
-fn _print_sequence(result: &Vec<(usize, i64)>) {
-    for (_, n) in result {
-        print!("{} ", n);
-    }
-    println!();
+fn calculate_result(ring: &IndexedRing<i64>, handles: &[RingHandle]) -> i64 {
+    let zero = handles
+        .iter()
+        .find(|h| *ring.get(**h) == 0)
+        .expect("Input should contain a zero");
+    let start_index = ring.index_of(*zero);
+
+    [1000, 2000, 3000]
+        .iter()
+        .map(|offset| ring.get(ring.at(start_index + offset)))
+        .sum()
 }
 
 #[cfg(test)]
@@ -1,21 +1,32 @@
-extern crate aoc_helper;
-use aoc_helper::{math, vec2d::Vec2D, uvec2d::UVec2D};
+use aoc_helper::{
+    cube::CubeNet,
+    prelude::*,
+    runner::{ProcessAndWrite, Runner},
+};
 
 fn main() {
-    let input = read_input("input.txt");
+    let runner = Runner::from_input_file(parse);
+    runner.process_and_write_part_1(part_1);
+    runner.process_and_write_part_2(part_2);
+}
 
-    println!("Part 1: {}", part_1(&input.0, &input.1));
-    println!("Part 2: {}", part_2(&input.0, &input.1, 50));
+/// The map, with every row padded with spaces to one past the widest row, and more blank rows
+/// when it is shorter than that, and the path to walk on it
+struct Notes {
+    map: Vec<String>,
+    moves: String,
 }
 
-fn read_input(filename: &str) -> (Vec<String>, String) {
-    let string = std::fs::read_to_string(filename).expect("File not found");
+fn parse(string: String) -> Notes {
     let lines = string.lines().collect::<Vec<&str>>();
     let [map, moves] = lines[..].split(|l| l.is_empty()).collect::<Vec<&[&str]>>()[..] else {panic!("Input not valid")};
     let map: Vec<String> = map.iter().map(|s| s.to_owned().to_owned()).collect();
     let square_map = fill_map_to_square(map);
 
-    (square_map, moves.first().unwrap().to_owned().to_owned())
+    Notes {
+        map: square_map,
+        moves: moves.first().unwrap().to_owned().to_owned(),
+    }
 }
 
 fn fill_map_to_square(mut map: Vec<String>) -> Vec<String> {
@@ -37,23 +48,35 @@ fn fill_map_to_square(mut map: Vec<String>) -> Vec<String> {
     map
 }
 
-fn part_1(map: &[String], moves: &str) -> usize {
-    walk_path(map, moves, false, 0)
+fn part_1(notes: &Notes) -> usize {
+    walk_path(&notes.map, &notes.moves, None)
 }
 
-fn part_2(map: &[String], moves: &str, map_size: u32) -> usize {
-    walk_path(map, moves, true, map_size)
+/// The size of the cube faces (50 for the input, 4 for the example) is found by `CubeNet`
+fn part_2(notes: &Notes) -> usize {
+    let cube = CubeNet::from_map(&notes.map);
+    walk_path(&notes.map, &notes.moves, Some(&cube))
 }
 
-fn walk_path(map: &[String], moves: &str, cube: bool, tile_size: u32) -> usize {
-    let directions = aoc_helper::navigation::get_adjecent_directions();
+/// In the order of the facing values used for the score, turning right goes to the next one
+fn facing_directions() -> [Vec2D; 4] {
+    [
+        Vec2D::new(1, 0),
+        Vec2D::new(0, 1),
+        Vec2D::new(-1, 0),
+        Vec2D::new(0, -1),
+    ]
+}
+
+fn walk_path(map: &[String], moves: &str, cube: Option<&CubeNet>) -> usize {
+    let directions = facing_directions();
     let map_size = get_map_size(map);
     let mut current_direction = 0i32;
     let start_x = find_first_available_start_x(map);
     let start_y = 0;
     let mut current_point = UVec2D::new(start_x, start_y);
     let mut steps_string = "".to_owned();
-    for (_, _move) in moves.chars().enumerate() {
+    for _move in moves.chars() {
         match _move {
             'R' => {
                 walk(
@@ -64,7 +87,6 @@ fn walk_path(map: &[String], moves: &str, cube: bool, tile_size: u32) -> usize {
                     map,
                     &map_size,
                     cube,
-                    tile_size,
                 );
 
                 current_direction = math::positive_mod(current_direction + 1, 4);
@@ -78,7 +100,6 @@ fn walk_path(map: &[String], moves: &str, cube: bool, tile_size: u32) -> usize {
                     map,
                     &map_size,
                     cube,
-                    tile_size,
                 );
                 current_direction = math::positive_mod(current_direction - 1, 4);
             }
@@ -94,7 +115,6 @@ fn walk_path(map: &[String], moves: &str, cube: bool, tile_size: u32) -> usize {
         map,
         &map_size,
         cube,
-        tile_size,
     );
 
     calculate_score(current_point, current_direction)
@@ -128,8 +148,7 @@ fn walk(
     current_direction: &mut i32,
     map: &[String],
     map_size: &Vec2D,
-    cube: bool,
-    tile_size: u32,
+    cube: Option<&CubeNet>,
 ) {
     if steps_string.is_empty() {
         return;
@@ -150,7 +169,6 @@ fn walk(
                     map,
                     current_point,
                     cube,
-                    tile_size,
                 ) {
                     break;
                 }
@@ -173,16 +191,15 @@ fn find_wrappd_position(
     map_size: &Vec2D,
     map: &[String],
     current_point: &mut UVec2D,
-    cube: bool,
-    tile_size: u32,
+    cube: Option<&CubeNet>,
 ) -> bool {
     loop {
         *next_point = calculate_next_point(next_point, directions, current_direction, map_size);
 
         match map[next_point.y].chars().nth(next_point.x).unwrap() {
             ' ' => {
-                if cube {
-                    return wrap_cube(map, current_point, current_direction, tile_size);
+                if let Some(cube) = cube {
+                    return wrap_cube(map, current_point, directions, current_direction, cube);
                 }
             }
             '#' => return false,
@@ -199,130 +216,28 @@ fn find_wrappd_position(
 fn wrap_cube(
     map: &[String],
     current_point: &mut UVec2D,
+    directions: &[Vec2D],
     current_direction: &mut i32,
-    tile_size: u32,
+    cube: &CubeNet,
 ) -> bool {
-    let tile_index = *current_point / tile_size;
-    let position_in_tile = (*current_point - tile_index * tile_size).to_uvec2d_or_throw();
-
-    let (new_tile_index, new_direction) = find_new_tile(tile_index, current_direction);
-
-    let rotation = math::positive_mod(*current_direction - new_direction, 4);
-    let offset = tile_size as i32 - 1;
-
-    let mut offset_position = position_in_tile * 2 - offset;
-
-    for _ in 0..rotation {
-        offset_position.rotate_right();
-    }
-
-    let new_position_in_tile = (offset_position + offset) / 2;
-
-    let mut x_offset = 0;
-    let mut y_offset = 0;
-
-    match new_direction {
-        0 => x_offset = -offset,
-        1 => y_offset = -offset,
-        2 => x_offset = offset,
-        3 => y_offset = offset,
-        _ => panic!("invalid rotation"),
-    }
-
-    let new_position = (new_position_in_tile
-        + new_tile_index * tile_size as i32
-        + Vec2D::new(x_offset, y_offset))
-    .to_uvec2d_or_throw();
-
-    draw_map(
-        map,
-        current_point,
-        current_direction,
-        &new_position,
-        new_direction,
-    );
+    let position = Vec2D::new(current_point.x as i32, current_point.y as i32);
+    let (new_position, new_direction) =
+        cube.wrap(position, directions[*current_direction as usize]);
+    let new_position = new_position.to_uvec2d_or_throw();
 
     if map[new_position.y].chars().nth(new_position.x).unwrap() == '#' {
         return false;
     }
 
     *current_point = new_position;
-    *current_direction = new_direction;
+    *current_direction = directions
+        .iter()
+        .position(|d| *d == new_direction)
+        .expect("cube should return one of the directions") as i32;
 
     true
 }
 
-fn find_new_tile(tile_index: UVec2D, current_direction: &mut i32) -> (Vec2D, i32) {
-    // note this mapping only works for input.txt
-    let offset_map = vec![
-        (UVec2D::new(0, 2), 2, Vec2D::new(1, 0), 0),
-        (UVec2D::new(0, 2), 3, Vec2D::new(1, 1), 0),
-        (UVec2D::new(0, 3), 0, Vec2D::new(1, 2), 3),
-        (UVec2D::new(0, 3), 1, Vec2D::new(2, 0), 1),
-        (UVec2D::new(0, 3), 2, Vec2D::new(1, 0), 1),
-        (UVec2D::new(1, 0), 2, Vec2D::new(0, 2), 0),
-        (UVec2D::new(1, 0), 3, Vec2D::new(0, 3), 0),
-        (UVec2D::new(1, 1), 2, Vec2D::new(0, 2), 1),
-        (UVec2D::new(1, 1), 0, Vec2D::new(2, 0), 3),
-        (UVec2D::new(1, 2), 1, Vec2D::new(0, 3), 2),
-        (UVec2D::new(1, 2), 0, Vec2D::new(2, 0), 2),
-        (UVec2D::new(2, 0), 3, Vec2D::new(0, 3), 3),
-        (UVec2D::new(2, 0), 1, Vec2D::new(1, 1), 2),
-        (UVec2D::new(2, 0), 0, Vec2D::new(1, 2), 2),
-    ];
-
-    for (source, source_rotation, tile_index_to_test, target_rotation) in offset_map {
-        if source == tile_index && source_rotation == *current_direction {
-            return (tile_index_to_test, target_rotation);
-        }
-    }
-
-    panic!("Mapping not found")
-}
-
-fn draw_map(
-    map: &[String],
-    current_point: &mut UVec2D,
-    current_direction: &mut i32,
-    next_point: &UVec2D,
-    new_direction: i32,
-) {
-    for (y, line) in map.iter().enumerate() {
-        if y % 8 != 0 && y != current_point.y && y != next_point.y {
-            continue;
-        }
-        for (x, char) in line.chars().enumerate() {
-            if x % 8 != 0 && x != current_point.x && x != next_point.x {
-                continue;
-            }
-            if current_point.x == x && current_point.y == y {
-                match *current_direction {
-                    0 => print!(">"),
-                    1 => print!("V"),
-                    2 => print!("<"),
-                    3 => print!("^"),
-                    _ => panic!(),
-                }
-            } else if next_point.x == x && next_point.y == y {
-                match new_direction {
-                    0 => print!(">"),
-                    1 => print!("V"),
-                    2 => print!("<"),
-                    3 => print!("^"),
-                    _ => panic!(),
-                }
-            } else if char == '.' {
-                print!(".");
-            } else {
-                print!("{}", char);
-            }
-        }
-        println!();
-    }
-    //use std::io::stdin;
-    //stdin().read_line(&mut "".to_string());
-}
-
 fn calculate_next_point(
     point: &UVec2D,
     directions: &[Vec2D],
@@ -337,16 +252,15 @@ fn calculate_next_point(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_helper::runner::ProcessAndAssert;
 
     #[test]
     fn part_1_works() {
-        let input = read_input("test.txt");
-        assert_eq!(part_1(&input.0, &input.1), 6032);
+        Runner::from_test_file(parse).process_and_assert(part_1, 6032)
     }
 
     #[test]
     fn part_2_works() {
-        let input = read_input("test.txt");
-        assert_eq!(part_2(&input.0, &input.1, 4), 5031);
+        Runner::from_test_file(parse).process_and_assert(part_2, 5031)
     }
 }
@@ -1,5 +1,4 @@
-use std::{mem, collections::HashMap};
-use aoc_helper::{collections::PriorityQueue, vectors::Vec2D, math, navigation};
+use aoc_helper::{math, navigation, search::TimeExpanded, vectors::Vec2D};
 
 extern crate aoc_helper;
 
@@ -20,82 +19,55 @@ fn read_input(filename: &str) -> Vec<Vec<char>> {
         .collect::<Vec<Vec<char>>>()
 }
 
-fn part_1(map: &[Vec<char>]) -> i32 {
-    let bounds = determine_bounds(map);
-    let start_point = Vec2D::new(1, 0);
-    let end_point = Vec2D::new(bounds.x - 2, bounds.y - 1);
+fn part_1(map: &[Vec<char>]) -> usize {
+    let (start_point, end_point) = start_and_end(map);
 
-    traverse(start_point, end_point, 0, map) 
+    traverse(&[start_point, end_point], map)
 }
 
-fn part_2(map: &[Vec<char>]) -> i32 {
-    let bounds = determine_bounds(map);
-    let mut start_point = Vec2D::new(1, 0);
-    let mut end_point = Vec2D::new(bounds.x - 2, bounds.y - 1);
-
-    let mut total_turns = 0;
+fn part_2(map: &[Vec<char>]) -> usize {
+    let (start_point, end_point) = start_and_end(map);
 
-    for _ in 0..3{
-        let turns = traverse(start_point, end_point, total_turns, map);
-
-        total_turns += turns;
-        mem::swap(&mut start_point, &mut end_point);
-    }
+    traverse(&[start_point, end_point, start_point, end_point], map)
+}
 
-    total_turns
+fn start_and_end(map: &[Vec<char>]) -> (Vec2D, Vec2D) {
+    let bounds = determine_bounds(map);
+    (Vec2D::new(1, 0), Vec2D::new(bounds.x - 2, bounds.y - 1))
 }
 
-fn traverse(
-    start_point: Vec2D,
-    end_point: Vec2D,
-    start_turn: i32,
-    map: &[Vec<char>],
-) -> i32 {
+fn traverse(waypoints: &[Vec2D], map: &[Vec<char>]) -> usize {
     let bounds = determine_bounds(map);
-    let directions = navigation::get_adjecent_directions_including_self();
-
-    let shortest_distance = start_point.manhattan_distance(end_point) as i32;
-    let mut seen_options = HashMap::new();
-    let mut collection = PriorityQueue::new();
-    collection.push((start_point, start_turn), shortest_distance);
-    loop {
-        if let Some((position, turn)) = collection.pop_lowest() {
-
-            for direction in directions {
-                let target_position = position + direction;
-
-                if target_position == end_point {
-                    return turn - start_turn;
-                }
-
-                if target_position.is_in_bounds(bounds)
-                    && is_empty(target_position, map, turn, bounds)
-                {
-                    let next_turn = turn + 1;
-                    
-                    if seen_options.contains_key(&(target_position, next_turn)) {
-                        continue;
-                    }
-                    
-                    let remaining_distance = target_position.manhattan_distance(end_point) as i32;
-
-                    collection.push((target_position, next_turn), next_turn + remaining_distance);
-                    seen_options.insert((target_position, next_turn), ());
-                }
-            }
-        } else {
-            panic!("No result found!")
-        }
-    }
+    // the blizzards are back where they started after they crossed the valley both ways
+    let period = math::lcm(bounds.x - 2, bounds.y - 2) as usize;
+    let valley = TimeExpanded::new(bounds, period, |position, turn| {
+        !is_empty(position, map, turn as i32, bounds)
+    });
+
+    valley
+        .trip(
+            waypoints,
+            0,
+            &navigation::get_adjecent_directions_including_self(),
+        )
+        .expect("No result found!")
 }
 
 fn is_empty(target_position: Vec2D, map: &[Vec<char>], turn: i32, bounds: Bounds) -> bool {
-    // calculate relative to the inner rectangle
+    if map[target_position.y as usize][target_position.x as usize] == '#' {
+        return false;
+    }
+
+    // calculate relative to the inner rectangle, blizzards never leave it
     let normalized_x = target_position.x - 1;
     let normalized_y = target_position.y - 1;
     let inner_width = bounds.x - 2;
     let inner_height = bounds.y - 2;
 
+    if !Vec2D::new(normalized_x, normalized_y).is_in_bounds(Vec2D::new(inner_width, inner_height)) {
+        return true;
+    }
+
     // find the positions a storm would have come from to reach this position
     let source_right = (math::positive_mod(normalized_x - turn, inner_width) + 1) as usize;
     let source_left = (math::positive_mod(normalized_x + turn, inner_width) + 1) as usize;
@@ -106,7 +78,6 @@ fn is_empty(target_position: Vec2D, map: &[Vec<char>], turn: i32, bounds: Bounds
         && map[target_position.y as usize][source_left] != '<'
         && map[source_down][target_position.x as usize] != 'v'
         && map[source_up][target_position.x as usize] != '^'
-        && map[target_position.y as usize][target_position.x as usize] != '#'
 }
 
 fn determine_bounds(input: &[Vec<char>]) -> Bounds {